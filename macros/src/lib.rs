@@ -0,0 +1,676 @@
+//! Proc-macro backend for [guilt-by-association](https://docs.rs/guilt-by-association)
+//!
+//! Don't use this crate directly: enable the `proc-macro` feature of guilt-by-association, which
+//! re-exports `guilty!` from here.
+//!
+//! Plain trait and impl definitions, and const accesses, are rewritten directly. Unlike the
+//! macro_rules engine, this works with any item header (generics, where clauses, paths...), and
+//! const accesses work with any qualified path. Everything
+//! else (items or consts with `#[guilty(...)]` options, the special forms like
+//! `guilty!(const $t, $name)`, array impls, and anything this backend can't make sense of) is
+//! handed over to the macro_rules engine, so the two backends expand the inputs they both accept
+//! identically -- except that this backend also adds rustdoc search aliases like `Trait::CONST`
+//! to the functions generated for consts in traits, which macro_rules can't build.
+//!
+//! The functions are named `__guilty_const_CONST`, unless the trait or impl has the
+//! `#[guilty(unmangled)]` option, and accesses call that name, unless they start with
+//! `unmangled`. The engine can't build the names either, so this crate also provides the hidden
+//! `__guilty_mangle!`, through which the engine outputs its items and accesses.
+//!
+//! An invocation holding several items is split into them first; each trait or impl is then
+//! treated as above, and any other item goes to the engine's `guilty_mod!`, which passes it
+//! through.
+//!
+//! With the `const-fn` feature, the functions generated for the consts of inherent impls are const
+//! fns, as they are with the engine.
+//!
+//! With the `real-consts` feature, there is nothing to rewrite, and all inputs are handed over to
+//! the macro_rules pass-through instead.
+
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use std::iter::FromIterator;
+
+/// Macro for declaring/implementing traits with fake associated consts
+///
+/// See the documentation of guilt-by-association for more.
+#[proc_macro]
+pub fn guilty(input: TokenStream) -> TokenStream {
+    if cfg!(feature = "real-consts") {
+        return delegate("__guilty_real", input);
+    }
+    let tokens = input.clone().into_iter().collect::<Vec<_>>();
+    let items = split_items(&tokens);
+    if items.len() > 1 {
+        // the traits and impls are rewritten one at a time, the other items go through guilty_mod!
+        // as with the engine
+        return TokenStream::from_iter(items.into_iter().map(|item| {
+            let input = TokenStream::from_iter(item.iter().cloned());
+            if is_guilty_item(item) { guilty(input) } else { delegate("guilty_mod", input) }
+        }));
+    }
+    // the consts of an item with the unmangled option keep their names, as do those accessed
+    // after unmangled (anything this backend can't rewrite goes to the engine, which has both)
+    if let Some(tokens) = without_unmangled_option(&tokens) {
+        if let Some(output) = rewrite_item(&tokens, false) {
+            return output;
+        }
+    }
+    if is_keyword(tokens.first(), "unmangled") {
+        if let Some(output) = rewrite_access(&tokens[1..], false) {
+            return output;
+        }
+    }
+    match rewrite_item(&tokens, true).or_else(|| rewrite_access(&tokens, true)) {
+        Some(output) => output,
+        None => delegate("__guilty_rules", input),
+    }
+}
+
+/// Attribute form of `guilty!` for traits and impls
+///
+/// `#[guilty_item] trait Trait { ... }` is the same as `guilty! { trait Trait { ... } }`, and
+/// `#[guilty_item(options...)]` is the same as a leading `#[guilty(options...)]`.
+#[proc_macro_attribute]
+pub fn guilty_item(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut input = TokenStream::new();
+    if !args.is_empty() {
+        let mut options = "guilty".parse::<TokenStream>().unwrap();
+        options.extend(Some(TokenTree::Group(Group::new(Delimiter::Parenthesis, args))));
+        input.extend(vec![punct('#', Spacing::Alone), TokenTree::Group(Group::new(Delimiter::Bracket, options))]);
+    }
+    input.extend(item);
+    guilty(input)
+}
+
+/// Names the functions generated for consts by the macro_rules engine, which can't build
+/// identifiers itself
+///
+/// The input is `[mode] tokens...`, in which each `__guilty_mangle NAME` becomes
+/// `__guilty_const_NAME`, or just `NAME` if the mode (or a `#[__guilty_mangle(...)]` attribute on
+/// an item at the top level, which is dropped) contains `unmangled`. A marker in front of a
+/// fragment, such as a path captured as `$target:expr`, applies to the last name in it.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __guilty_mangle(input: TokenStream) -> TokenStream {
+    let mut tokens = input.into_iter().collect::<Vec<_>>();
+    let mut mangle = match tokens.first() {
+        Some(TokenTree::Group(mode)) if mode.delimiter() == Delimiter::Bracket => !mentions_unmangled(&mode.stream()),
+        _ => return compile_error("guilty!: internal error: expected the mangling mode"),
+    };
+    tokens.remove(0);
+
+    // the mode attributes of the items
+    let mut i = 0;
+    while i < tokens.len() {
+        match attribute_at(&tokens, i).map(|attr| flatten(&attr.stream())) {
+            Some(ref attr) if is_keyword(attr.first(), "__guilty_mangle") => {
+                if mentions_unmangled(&TokenStream::from_iter(attr.iter().cloned())) {
+                    mangle = false;
+                }
+                tokens.drain(i..i + 2);
+            }
+            _ => i += 1,
+        }
+    }
+
+    mangle_names(tokens, mangle)
+}
+
+/// Rewrites the `__guilty_mangle NAME` markers, in the groups as well
+fn mangle_names(tokens: Vec<TokenTree>, mangle: bool) -> TokenStream {
+    match rewrite_markers(&tokens, mangle) {
+        Ok(Some(tokens)) => TokenStream::from_iter(tokens),
+        Ok(None) => TokenStream::from_iter(tokens),
+        Err(message) => compile_error(message),
+    }
+}
+
+/// The tokens with the markers rewritten, or None if there are none
+///
+/// The groups without markers are kept as they are, since a fragment (in an invisible group)
+/// which is taken apart is printed differently by `stringify!`.
+fn rewrite_markers(tokens: &[TokenTree], mangle: bool) -> Result<Option<Vec<TokenTree>>, &'static str> {
+    let mut output = Vec::with_capacity(tokens.len());
+    let mut changed = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i] {
+            // a nested call of the macro itself is left alone
+            TokenTree::Ident(ref marker) if marker.to_string() == "__guilty_mangle"
+                && !matches!(tokens.get(i + 1), Some(TokenTree::Punct(_))) => {
+                match tokens.get(i + 1) {
+                    Some(name @ TokenTree::Ident(_)) => output.push(const_fn_name(name, mangle)),
+                    // a fragment: the name is its last token
+                    Some(TokenTree::Group(ref group)) if group.delimiter() == Delimiter::None => {
+                        let inner = group.stream().into_iter().collect::<Vec<_>>();
+                        match inner.split_last() {
+                            Some((last, init)) => {
+                                output.extend(rewrite_markers(init, mangle)?.unwrap_or_else(|| init.to_vec()));
+                                output.push(const_fn_name(last, mangle));
+                            }
+                            None => return Err("guilty!: internal error: expected a name after the marker"),
+                        }
+                    }
+                    _ => return Err("guilty!: internal error: expected a name after the marker"),
+                }
+                changed = true;
+                i += 2;
+                continue;
+            }
+            TokenTree::Group(ref group) => {
+                let inner = group.stream().into_iter().collect::<Vec<_>>();
+                match rewrite_markers(&inner, mangle)? {
+                    Some(inner) => {
+                        let mut rewritten = Group::new(group.delimiter(), TokenStream::from_iter(inner));
+                        rewritten.set_span(group.span());
+                        output.push(TokenTree::Group(rewritten));
+                        changed = true;
+                    }
+                    None => output.push(tokens[i].clone()),
+                }
+            }
+            ref token => output.push(token.clone()),
+        }
+        i += 1;
+    }
+    Ok(if changed { Some(output) } else { None })
+}
+
+/// Whether the tokens contain `unmangled`, in any group
+fn mentions_unmangled(tokens: &TokenStream) -> bool {
+    tokens.clone().into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident.to_string() == "unmangled",
+        TokenTree::Group(group) => mentions_unmangled(&group.stream()),
+        _ => false,
+    })
+}
+
+/// The tokens, with the invisible groups around fragments taken apart
+fn flatten(tokens: &TokenStream) -> Vec<TokenTree> {
+    tokens.clone().into_iter().flat_map(|token| match token {
+        TokenTree::Group(ref group) if group.delimiter() == Delimiter::None => flatten(&group.stream()),
+        token => vec![token],
+    }).collect()
+}
+
+/// A `compile_error!` with the given message
+fn compile_error(message: &str) -> TokenStream {
+    let mut output = "compile_error!".parse::<TokenStream>().unwrap();
+    let message = TokenStream::from_iter(Some(TokenTree::Literal(Literal::string(message))));
+    output.extend(Some(TokenTree::Group(Group::new(Delimiter::Parenthesis, message))));
+    output.extend(Some(punct(';', Spacing::Alone)));
+    output
+}
+
+/// Hands the input over to one of the macro_rules macros of guilt-by-association
+fn delegate(name: &str, input: TokenStream) -> TokenStream {
+    let mut output = format!("::guilt_by_association::{}!", name).parse::<TokenStream>().unwrap();
+    output.extend(Some(TokenTree::Group(Group::new(Delimiter::Brace, input))));
+    output
+}
+
+/// Splits the input into items, each ending at a semicolon or at a braced block followed by the
+/// start of another item (consts and statics end at the semicolon, past any blocks in the value)
+fn split_items(tokens: &[TokenTree]) -> Vec<&[TokenTree]> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    while start < tokens.len() {
+        let mut i = start;
+        while attribute_at(tokens, i).is_some() {
+            i += 2;
+        }
+        let value = is_item(tokens, i, "const") || is_item(tokens, i, "static")
+            || (is_keyword(tokens.get(i), "pub") && (is_item(tokens, i + 1, "const") || is_item(tokens, i + 1, "static")));
+        let mut end = tokens.len();
+        while i < tokens.len() {
+            let ends = match tokens[i] {
+                TokenTree::Punct(ref punct) => punct.as_char() == ';',
+                TokenTree::Group(ref group) if group.delimiter() == Delimiter::Brace && !value =>
+                    match tokens.get(i + 1) {
+                        None | Some(TokenTree::Ident(_)) => true,
+                        Some(next) => is_punct(next, '#'),
+                    },
+                _ => false,
+            };
+            i += 1;
+            if ends {
+                end = i;
+                break;
+            }
+        }
+        items.push(&tokens[start..end]);
+        start = end;
+    }
+    items
+}
+
+/// Whether an item is a trait or impl (including an extension trait), after any attributes and
+/// qualifiers
+fn is_guilty_item(tokens: &[TokenTree]) -> bool {
+    let mut i = 0;
+    while attribute_at(tokens, i).is_some() {
+        i += 2;
+    }
+    loop {
+        match tokens.get(i) {
+            Some(TokenTree::Ident(ident)) => match &*ident.to_string() {
+                "trait" | "impl" | "extend" => return true,
+                "pub" | "unsafe" => i += 1,
+                _ => return false,
+            },
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis && i > 0 => i += 1,
+            _ => return false,
+        }
+    }
+}
+
+/// The item without its `#[guilty(unmangled)]` attribute, or None if it doesn't have one with
+/// only that option
+fn without_unmangled_option(tokens: &[TokenTree]) -> Option<Vec<TokenTree>> {
+    let mut i = 0;
+    while let Some(attr) = attribute_at(tokens, i) {
+        let attr = attr.stream().into_iter().collect::<Vec<_>>();
+        if let (Some(name), Some(TokenTree::Group(options)), 2) = (attr.first(), attr.get(1), attr.len()) {
+            let options = options.stream().into_iter().collect::<Vec<_>>();
+            if is_keyword(Some(name), "guilty") && options.len() == 1 && is_keyword(options.first(), "unmangled") {
+                let mut item = tokens[..i].to_vec();
+                item.extend(tokens[i + 2..].iter().cloned());
+                return Some(item);
+            }
+        }
+        i += 2;
+    }
+    None
+}
+
+/// The name of the function generated for a const, which is `__guilty_const_NAME` unless the
+/// names are unmangled
+fn const_fn_name(name: &TokenTree, mangle: bool) -> TokenTree {
+    match *name {
+        TokenTree::Ident(ref ident) if mangle => {
+            TokenTree::Ident(Ident::new(&format!("__guilty_const_{}", ident), ident.span()))
+        }
+        _ => name.clone(),
+    }
+}
+
+/// Rewrites a trait or impl (with mangled names unless `mangle` is unset), or returns None if the
+/// input is something else
+fn rewrite_item(tokens: &[TokenTree], mangle: bool) -> Option<TokenStream> {
+    let (body, header) = match tokens.split_last() {
+        Some((TokenTree::Group(group), header)) if group.delimiter() == Delimiter::Brace => (group, header),
+        _ => return None,
+    };
+
+    // attributes, except for guilty options
+    let mut i = 0;
+    while attribute_at(header, i).is_some() {
+        if is_guilty_attribute(&header[i..]) {
+            return None;
+        }
+        i += 2;
+    }
+
+    // qualifiers, then the keyword
+    let is_trait = loop {
+        match header.get(i) {
+            Some(TokenTree::Ident(ident)) => match &*ident.to_string() {
+                "trait" => break true,
+                "impl" => break false,
+                "pub" | "unsafe" => i += 1,
+                _ => return None,
+            },
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis && i > 0 => i += 1,
+            _ => return None,
+        }
+    };
+
+    // impls for a range of array lengths, or for several types, are stamped out by the engine
+    if !is_trait && (header.iter().any(is_array_range) || is_batch_impl(&header[i..])) {
+        return None;
+    }
+
+    // traits are named, for the search aliases of their consts
+    let trait_name = match header.get(i + 1) {
+        Some(TokenTree::Ident(name)) if is_trait => Some(name.to_string()),
+        _ if is_trait => return None,
+        _ => None,
+    };
+
+    // impls without a for are inherent (an impl may be taken for a trait impl because of a for in
+    // a bound, which only means that its functions are not const fns)
+    let inherent = !is_trait && !header.iter().any(|token| is_keyword(Some(token), "for"));
+
+    let body = rewrite_body(&body.stream().into_iter().collect::<Vec<_>>(), trait_name.as_deref(), inherent,
+                            mangle)?;
+    let mut output = TokenStream::from_iter(header.iter().cloned());
+    output.extend(Some(TokenTree::Group(Group::new(Delimiter::Brace, body))));
+    Some(output)
+}
+
+/// Rewrites a const access (`Type::CONST` or `<Type as Trait>::CONST`, with any paths and
+/// generics, calling the mangled name unless `mangle` is unset), or returns None if the input is
+/// something else
+fn rewrite_access(tokens: &[TokenTree], mangle: bool) -> Option<TokenStream> {
+    let n = tokens.len();
+    let starts_like_path = match (tokens.first(), tokens.get(1)) {
+        (Some(first), _) if is_punct(first, '<') => true,
+        (Some(first), _) if is_joint_punct(first, ':') => true,
+        (Some(TokenTree::Ident(_)), Some(second)) => is_punct(second, ':'),
+        _ => false,
+    };
+    let ends_with_const = n >= 3
+        && is_joint_punct(&tokens[n - 3], ':')
+        && is_punct(&tokens[n - 2], ':')
+        && matches!(tokens[n - 1], TokenTree::Ident(_));
+    if !(starts_like_path && ends_with_const) {
+        return None;
+    }
+
+    let mut call = TokenStream::from_iter(tokens[..n - 1].iter().cloned());
+    call.extend(Some(const_fn_name(&tokens[n - 1], mangle)));
+    call.extend(Some(TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::new()))));
+    Some(TokenStream::from_iter(Some(TokenTree::Group(Group::new(Delimiter::Brace, call)))))
+}
+
+/// Moves the non-const items of a trait/impl body to the front, followed by the consts turned into
+/// static functions, and the statics into functions returning references to them (the trait name
+/// is given for traits, and None for impls; the functions for the consts of inherent impls are
+/// const fns with the const-fn feature; with `mangle`, the functions are named
+/// `__guilty_const_NAME`, see const_fn_name)
+fn rewrite_body(body: &[TokenTree], trait_name: Option<&str>, inherent: bool, mangle: bool)
+    -> Option<TokenStream> {
+    let is_trait = trait_name.is_some();
+    let mut items = TokenStream::new();
+    let mut consts = TokenStream::new();
+
+    let mut i = 0;
+    while i < body.len() {
+        // a stray separator
+        if is_punct(&body[i], ',') || is_punct(&body[i], ';') {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while attribute_at(body, i).is_some() {
+            i += 2;
+        }
+        let attrs_end = i;
+        // a visibility, in an inherent impl
+        if is_keyword(body.get(i), "pub") {
+            i += 1;
+            if let Some(TokenTree::Group(group)) = body.get(i) {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    i += 1;
+                }
+            }
+        }
+        let vis = &body[attrs_end..i];
+        // a const marked default, in a specializable impl
+        let is_default = is_keyword(body.get(i), "default") && is_item(body, i + 1, "const");
+        if is_default {
+            i += 1;
+        }
+
+        let is_static = is_item(body, i, "static");
+        if is_item(body, i, "const") || is_static {
+            let attrs = &body[start..attrs_end];
+            // consts with guilty options are handled by the engine
+            if is_guilty_attribute(attrs) {
+                return None;
+            }
+            let name = body[i + 1].clone();
+            let (ty, end) = const_type(body, i + 3)?;
+            let value = match body.get(end) {
+                Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+                    let separator = const_value_end(body, end + 1)?;
+                    i = separator + 1;
+                    Some(TokenStream::from_iter(body[end + 1..separator].iter().cloned()))
+                }
+                _ if is_trait => {
+                    i = end + 1;
+                    None
+                }
+                _ => return None,
+            };
+            // statics have values in impls only, the engine reports the others
+            if is_static && is_trait == value.is_some() {
+                return None;
+            }
+
+            consts.extend(attrs.iter().cloned());
+            if let Some(trait_name) = trait_name {
+                consts.extend(doc_alias(&format!("{}::{}", trait_name, name)));
+            }
+            if let Some(literal) = value.as_ref().filter(|_| !is_static).and_then(literal_value) {
+                let label = if is_trait { "Default" } else { "Value" };
+                consts.extend(doc(""));
+                consts.extend(doc(&format!("{}: `{}`", label, literal)));
+            }
+            consts.extend("#[allow(non_snake_case)]".parse::<TokenStream>().unwrap());
+            consts.extend(vis.iter().cloned());
+            if is_default {
+                consts.extend("default".parse::<TokenStream>().unwrap());
+            }
+            if inherent && !is_static && cfg!(feature = "const-fn") {
+                consts.extend("const".parse::<TokenStream>().unwrap());
+            }
+            consts.extend("fn".parse::<TokenStream>().unwrap());
+            consts.extend(Some(const_fn_name(&name, mangle)));
+            consts.extend(Some(TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::new()))));
+            consts.extend(vec![punct('-', Spacing::Joint), punct('>', Spacing::Alone)]);
+            if is_static {
+                consts.extend("&'static".parse::<TokenStream>().unwrap());
+            }
+            consts.extend(ty.clone());
+            match value {
+                Some(value) if is_static => {
+                    let mut block = "static VALUE:".parse::<TokenStream>().unwrap();
+                    block.extend(ty);
+                    block.extend(Some(punct('=', Spacing::Alone)));
+                    block.extend(value);
+                    block.extend("; &VALUE".parse::<TokenStream>().unwrap());
+                    consts.extend(Some(TokenTree::Group(Group::new(Delimiter::Brace, block))));
+                }
+                Some(value) => consts.extend(Some(TokenTree::Group(Group::new(Delimiter::Brace, value)))),
+                None => consts.extend(Some(punct(';', Spacing::Alone))),
+            }
+        } else {
+            // any other item ends with a semicolon or a braced body
+            let end = (i..body.len()).find(|&j| match body[j] {
+                TokenTree::Punct(ref punct) => punct.as_char() == ';',
+                TokenTree::Group(ref group) => group.delimiter() == Delimiter::Brace,
+                _ => false,
+            })?;
+            items.extend(body[start..end + 1].iter().cloned());
+            i = end + 1;
+        }
+    }
+
+    items.extend(consts);
+    Some(items)
+}
+
+/// Returns the contents of the attribute starting at `tokens[i]`, if there is one
+fn attribute_at(tokens: &[TokenTree], i: usize) -> Option<&Group> {
+    match (tokens.get(i), tokens.get(i + 1)) {
+        (Some(hash), Some(TokenTree::Group(group)))
+            if is_punct(hash, '#') && group.delimiter() == Delimiter::Bracket => Some(group),
+        _ => None,
+    }
+}
+
+/// Whether the tokens start with a `#[guilty(...)]` attribute
+fn is_guilty_attribute(tokens: &[TokenTree]) -> bool {
+    match attribute_at(tokens, 0).and_then(|attr| attr.stream().into_iter().next()) {
+        Some(TokenTree::Ident(ident)) => ident.to_string() == "guilty",
+        _ => false,
+    }
+}
+
+/// Whether the token is the given keyword
+fn is_keyword(token: Option<&TokenTree>, keyword: &str) -> bool {
+    match token {
+        Some(TokenTree::Ident(ident)) => ident.to_string() == keyword,
+        _ => false,
+    }
+}
+
+/// Whether `tokens[i..]` starts with `keyword NAME :`, as in `const NAME :` (as opposed to, say, a
+/// `const fn`) or `static NAME :`
+fn is_item(tokens: &[TokenTree], i: usize, keyword: &str) -> bool {
+    match (tokens.get(i), tokens.get(i + 1), tokens.get(i + 2)) {
+        (Some(TokenTree::Ident(kw)), Some(TokenTree::Ident(_)), Some(colon)) =>
+            kw.to_string() == keyword && is_punct(colon, ':'),
+        _ => false,
+    }
+}
+
+/// Collects the type of a const, starting at `tokens[i]` and ending before the `=`, `;` or `,`
+/// that follows it (outside of any angle brackets)
+fn const_type(tokens: &[TokenTree], mut i: usize) -> Option<(TokenStream, usize)> {
+    let start = i;
+    let mut depth = 0usize;
+    while let Some(token) = tokens.get(i) {
+        if let TokenTree::Punct(ref punct) = *token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                // skip the > in ->
+                '>' if i > start && is_joint_punct(&tokens[i - 1], '-') => {}
+                '>' => depth = depth.checked_sub(1)?,
+                '=' | ';' | ',' if depth == 0 => {
+                    return if i > start {
+                        Some((TokenStream::from_iter(tokens[start..i].iter().cloned()), i))
+                    } else {
+                        None
+                    };
+                }
+                _ => {}
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Finds the `;` or `,` ending the value of a const which starts at `tokens[i]`
+///
+/// A comma can also separate generic arguments, so angle brackets are counted, but only after `::`
+/// or at the start of the value (where they can't be comparisons), and the parameters of a
+/// closure at the start of the value are skipped.
+fn const_value_end(tokens: &[TokenTree], mut i: usize) -> Option<usize> {
+    let params = if is_keyword(tokens.get(i), "move") { i + 1 } else { i };
+    if tokens.get(params).map_or(false, |token| is_punct(token, '|')) {
+        i = params + 1 + tokens[params + 1..].iter().position(|token| is_punct(token, '|'))?;
+    }
+    let mut depth = 0usize;
+    for j in i..tokens.len() {
+        if let TokenTree::Punct(ref punct) = tokens[j] {
+            match punct.as_char() {
+                '<' if j == i || (j >= i + 2 && is_joint_punct(&tokens[j - 2], ':') && is_punct(&tokens[j - 1], ':')) => {
+                    depth += 1
+                }
+                // the second < of a << at the start
+                '<' if j == i + 1 && is_joint_punct(&tokens[i], '<') => depth += 1,
+                '>' if depth > 0 => depth -= 1,
+                ';' => return Some(j),
+                ',' if depth == 0 => return Some(j),
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+/// Whether an impl header (from the keyword on) lists several types, as in `impl Trait for A, B`
+///
+/// Only the commas outside of angle brackets count, and the where clause is not looked at.
+fn is_batch_impl(header: &[TokenTree]) -> bool {
+    let mut depth = 0usize;
+    let mut after_for = false;
+    for (j, token) in header.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident) if depth == 0 => match &*ident.to_string() {
+                "for" => after_for = true,
+                "where" => return false,
+                _ => {}
+            },
+            TokenTree::Punct(punct) => match punct.as_char() {
+                '<' => depth += 1,
+                // not the > of a ->
+                '>' if depth > 0 && !(j > 0 && is_joint_punct(&header[j - 1], '-')) => depth -= 1,
+                ',' if depth == 0 && after_for => return true,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Whether the token is an array type with a range of lengths, like `[T; 0..=32]`
+fn is_array_range(token: &TokenTree) -> bool {
+    match *token {
+        TokenTree::Group(ref group) if group.delimiter() == Delimiter::Bracket => {
+            let tokens = group.stream().into_iter().collect::<Vec<_>>();
+            tokens.windows(3).any(|w| is_joint_punct(&w[0], '.') && is_joint_punct(&w[1], '.') && is_punct(&w[2], '='))
+        }
+        _ => false,
+    }
+}
+
+/// The text of a const value which is a literal (possibly negated), which is added to the docs
+fn literal_value(value: &TokenStream) -> Option<String> {
+    let tokens = value.clone().into_iter().collect::<Vec<_>>();
+    match tokens.as_slice() {
+        [TokenTree::Literal(literal)] => Some(literal.to_string()),
+        [minus, TokenTree::Literal(literal)] if is_punct(minus, '-') => Some(format!("-{}", literal)),
+        _ => None,
+    }
+}
+
+/// A `#[doc(alias = "...")]` attribute
+fn doc_alias(alias: &str) -> TokenStream {
+    let alias = TokenStream::from_iter(vec![
+        TokenTree::Ident(Ident::new("alias", Span::call_site())),
+        punct('=', Spacing::Alone),
+        TokenTree::Literal(Literal::string(alias)),
+    ]);
+    let attr = TokenStream::from_iter(vec![
+        TokenTree::Ident(Ident::new("doc", Span::call_site())),
+        TokenTree::Group(Group::new(Delimiter::Parenthesis, alias)),
+    ]);
+    TokenStream::from_iter(vec![punct('#', Spacing::Alone), TokenTree::Group(Group::new(Delimiter::Bracket, attr))])
+}
+
+/// A `#[doc = "..."]` attribute
+fn doc(text: &str) -> TokenStream {
+    let attr = TokenStream::from_iter(vec![
+        TokenTree::Ident(Ident::new("doc", Span::call_site())),
+        punct('=', Spacing::Alone),
+        TokenTree::Literal(Literal::string(text)),
+    ]);
+    TokenStream::from_iter(vec![punct('#', Spacing::Alone), TokenTree::Group(Group::new(Delimiter::Bracket, attr))])
+}
+
+fn is_punct(token: &TokenTree, c: char) -> bool {
+    match *token {
+        TokenTree::Punct(ref punct) => punct.as_char() == c,
+        _ => false,
+    }
+}
+
+fn is_joint_punct(token: &TokenTree, c: char) -> bool {
+    match *token {
+        TokenTree::Punct(ref punct) => punct.as_char() == c && punct.spacing() == Spacing::Joint,
+        _ => false,
+    }
+}
+
+fn punct(c: char, spacing: Spacing) -> TokenTree {
+    let mut punct = Punct::new(c, spacing);
+    punct.set_span(Span::call_site());
+    TokenTree::Punct(punct)
+}
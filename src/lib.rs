@@ -1,218 +1,6029 @@
 //! Macro for declaring/implementing traits with fake associated consts (in stable Rust)
 //!
-//! Currently very fragile in terms of syntax: does not support traits/impls with _any_ kind of
-//! generic parameters (either lifetimes or types).
+//! Currently very fragile in terms of syntax: traits may only have lifetime parameters (as in
+//! `trait Trait<'a> where Self: Sized`), not type parameters. Impls may be generic, as in
+//! `impl<T: Clone> Trait for Wrapper<T> where T: Default`, and name the trait and the type by
+//! paths, as in `impl config::Trait for crate::app::Settings`. Input that breaks these (or other)
+//! restrictions is reported with an error naming the restriction, such as "guilty!: trait `Trait`
+//! may only have lifetime parameters".
 //!
 //! The same macro is used for declaring a trait with associated consts, implementing such a trait,
-//! and accessing the consts.
+//! and accessing the consts. It also works for inherent impls, as in
+//! `impl Struct { pub const MAX: usize = 16; }`, whose consts become inherent functions with the
+//! same visibility (the guilty options are meant for trait impls, though).
+//! Traits may have any visibility, as in `pub(crate) trait`, `pub(super) trait` or
+//! `pub(in some::path) trait`, and so may the consts of inherent impls.
+//! Traits and impls may be `unsafe`, as in `unsafe trait Trait` and `unsafe impl Trait for Struct`
+//! (but the extra impls generated by `also_for_ref` are not, so that option can't be used there).
+//! Impls may carry attributes, as in `#[cfg(feature = "simd")] impl Trait for Struct`; a `cfg`
+//! applies to everything generated for the impl, and other attributes go on the impl itself.
 //!
-//! The syntax is the same as that proposed for associated consts, _except_ that:
-//!
-//! - all consts must be at the beginning of the trait/impl, before any functions
+//! The syntax is the same as that of associated consts, and consts may be mixed with the other
+//! items of the trait/impl in any order. Consts may be gated by `#[cfg(...)]` attributes,
+//! including pairs that define the same const for different configurations (as in
+//! `#[cfg(target_pointer_width = "64")] const WORDS: usize = 8;` followed by the 32-bit version);
+//! whatever the options generate for each const is gated the same way. A const may end with a
+//! comma instead of a semicolon, and stray separators between the items are ignored. The type
+//! of a const may be one of the trait's associated types, as in `const EMPTY: Self::Output;`,
+//! whether the `type Output;` item comes before or after it.
+//! The expansion takes a bounded number of steps per item, so traits and impls with many consts
+//! (a hundred or so, or about half that with commas, methods or options, and fewer if many of them
+//! have long types or values) fit in the default
+//! `#![recursion_limit]`.
 //!
 //! See the tests for example usage.
 //!
-//! At the moment they are not consts at all -- they simply expand to static functions with the
-//! same name as the declared const. You may therefore access the const by calling
-//! `Trait::CONST()`, or (for future proofing, in case the macro implementation changes), call the
-//! macro again to access the const, as `guilty!(Trait::CONST)`.
+//! The macros may be imported by path as well as with `#[macro_use] extern crate`, and they call
+//! each other through `$crate`, so they also work when re-exported from another crate:
+//!
+//! ```
+//! extern crate guilt_by_association;
+//! use guilt_by_association::guilty;
+//!
+//! guilty! {
+//!     pub trait Buffer {
+//!         const SIZE: usize;
+//!     }
+//! }
+//!
+//! struct Page;
+//! guilty! {
+//!     impl Buffer for Page {
+//!         const SIZE: usize = 4096;
+//!     }
+//! }
+//!
+//! # fn main() {
+//! assert_eq!(guilty!(Page::SIZE), 4096);
+//! # }
+//! ```
+//!
+//! When the value of a const (or the default, in a trait) is written as a literal, it is added to
+//! the documentation of the generated function, as "Value: `42`" (or "Default: `42`").
+//! With the `proc-macro` feature, the functions generated in traits also get a rustdoc search
+//! alias, so that searching for `Trait::CONST` finds them.
+//!
+//! At the moment they are not consts at all -- they simply expand to static functions, named
+//! `__guilty_const_CONST` after the declared const. You access the const by calling the macro
+//! again, as `guilty!(Trait::CONST)`, which also keeps working if the macro implementation
+//! changes. Consts of impls for types which
+//! are not plain names are accessed as `guilty!(<Vec<u8> as Trait>::CONST)` (or
+//! `guilty!(<Vec<u8>>::CONST)`, or `guilty!(Vec::<u8>::CONST)`), and in generic code the type
+//! may be `Self`, a type parameter or a projection, as in `guilty!(<T::Item as Trait>::CONST)`.
+//! The type and the trait may be named by any path, including ones that start with `crate::`,
+//! `super::`, `self::` or `::`, as in `guilty!(crate::config::Settings::MAX)` or
+//! `guilty!(<::app::Settings as ::traits::Config>::RETRIES)`.
+//!
+//! The mangled names can't collide with the methods of the types, so `guilty!(Struct::LEN)`
+//! finds the const even if `Struct` has a method `LEN`. It is still ambiguous when two traits
+//! implemented by `Struct` both have a `CONST`: the qualified access
+//! `guilty!(<Struct as Trait>::CONST)` always picks the const of the given trait, so it is the
+//! form to use when the names may overlap (and in macros, where they can't be known). To keep the
+//! consts apart from the other items of the trait as well, move them into their own trait with
+//! the `consts_mod` option below.
+//!
+//! Older versions named the functions after the consts, as `fn CONST()`, to be called as
+//! `Struct::CONST()`. The `unmangled` option below keeps those names, for code which calls the
+//! functions directly, or implements a trait which is not defined with `guilty!`; the consts of
+//! a trait or impl with the option are accessed as `guilty!(unmangled Struct::CONST)` (or any of
+//! the paths above, after `unmangled`), or called as before.
+//!
+//! On nightly, with `#![feature(specialization)]`, the consts of an impl may be marked `default`,
+//! as in `default const NAME: &'static str = "any";`, to become `default fn`s which more specific
+//! impls can override (`default fn` items are passed through). The options which add items to
+//! the impl, such as `fingerprint`, don't mark them `default`, so they can't be used in impls
+//! which are specialized.
+//!
+//! Traits may also declare statics, as in `static COUNTER: AtomicUsize;`, for state kept by each
+//! implementor, which impls define with a value, as in
+//! `static COUNTER: AtomicUsize = AtomicUsize::new(0);` (ending with a semicolon). The static is
+//! turned into a function returning a `&'static` reference to a static of the impl, and accessed
+//! the same way as a const, so `guilty!(Struct::COUNTER)` is a `&'static AtomicUsize`. As with any
+//! static, the type must be `Sync` and the value a const expression; in a generic impl, they can't
+//! mention the generic parameters (or `Self`), and all of the types share the one static. Statics
+//! are not available with the `real-consts` feature.
+//!
+//! An impl may list several types, as in `impl Trait for A, B, Wrapper<C> { ... }`, to implement
+//! the trait for each of them with the same body (and options, so those which name an item,
+//! such as `literals_mod`, can't be used there). With the `real-consts` feature, such an impl
+//! can't have generics or a where clause, and the trait must be a plain path.
+//!
+//! Like the standard library before const generics, `guilty! { impl Trait for [T; 0..=32] { ... } }`
+//! implements a trait for arrays of each length from 0 to 32, adding a `LEN: usize` const with the
+//! length (the body may be preceded by a where clause for `T`).
+//!
+//! Macros which have the type and the const name as separate fragments (say, a `$t:ty` and an
+//! `$name:ident` built up by the caller) can instead use `guilty!(const $t, $name)` or
+//! `guilty!(const $t as Trait, $name)`.
+//!
+//! To read a const from a concrete type that may not implement the trait, use
+//! `guilty_try_const!(<Struct as Trait>::CONST: Type else fallback)`. In a generic function,
+//! this always evaluates to the fallback (unless the function has the bound anyway).
+//!
+//! When renaming a const, `guilty_alias!` can keep the old name (or the new one) working as an
+//! alias of the other.
+//!
+//! `guilty_compose!` defines a trait combining the consts of several others, with a blanket impl.
+//!
+//! `guilty_carriers!` defines marker types carrying const values, one per value.
+//!
+//! `guilty_adapt!` implements a trait by mapping its consts from the impl of another one.
+//!
+//! `guilty_dispatch!` defines a function mapping the value of a const to the type that has it.
+//!
+//! `guilty_assert_unique!(Trait::CONST for A, B, C)` checks that the listed impls all have
+//! different values of a const.
+//!
+//! `guilty_assert_eq!(<A as Trait>::CONST, <B as Trait>::CONST)` compares two consts (or a const
+//! and a value), and `guilty_assert_type!(Struct::CONST: Type)` checks the type of a const at
+//! compile time, whichever backend is active.
+//!
+//! `guilty_select!("feature-a" => value, "feature-b" => value)` picks the value of a const
+//! according to which of a set of mutually exclusive cargo features is enabled.
+//!
+//! `guilty_expr!(A::X + B::Y * A::Z)` rewrites all of the const accesses in an expression (or a
+//! block) at once.
+//!
+//! `guilty_mod! { mod name { ... } }` rewrites all of the traits and impls in a module.
+//!
+//! One `guilty! { ... }` may also hold several items: a trait followed by its impls, say, with
+//! structs or functions in between. Each trait and impl is rewritten as if it had its own
+//! invocation, and any other item is passed through (modules are rewritten as by `guilty_mod!`).
+//! The items are split a token at a time, so a long list of them may need a higher
+//! `#![recursion_limit]`.
+//!
+//! Traits and impls may also be declared in a function body, say in a test. Only be careful with
+//! a `guilty!(...)` in parentheses that ends a block: the compiler reads a macro call there as the
+//! value of the block, which an item can't be, so end it with a `;`, or use braces, as in
+//! `guilty! { ... }`. The modules generated by `consts_mod` and `literals_mod` can't see the types
+//! declared in the function, as no nested module can, so with these options, the types of the
+//! consts must be declared outside the function.
+//!
+//! With the `proc-macro` feature, `guilty!` is instead a proc macro (from the companion crate
+//! guilt-by-association-macros), which rewrites traits and impls itself and so lifts the
+//! restrictions above: any generics, where clauses or paths may appear in the header. For
+//! everything else -- const accesses, items with guilty options -- it defers to the macro_rules
+//! engine, so code written for one backend expands the same way with the other. This feature also
+//! provides an attribute form, `#[guilty_item] trait Trait { ... }` (or
+//! `#[guilty_item(options...)]`), which is the same as wrapping the item in `guilty!`. The
+//! companion crate is a dependency without the feature as well, as both backends use it to
+//! mangle the names of the generated functions.
+//!
+//! On compilers with associated consts, the `real-consts` feature makes `guilty!` pass traits and
+//! impls through unchanged, so that the consts are real associated consts, and turns the accesses
+//! into plain paths: `guilty!(Struct::CONST)` is `Struct::CONST`. Code which always goes through
+//! `guilty!` can then switch between the two implementations with the feature alone. The guilty
+//! options, array impls and special forms like `guilty!(overridden Struct::CONST)` depend on the
+//! consts being functions, so they are errors with this feature; `guilty2!` and the other macros
+//! are not affected by it. Since the items are passed through as written, their consts must end
+//! with semicolons.
+//!
+//! With the `const-fn` feature, the functions generated for the consts of inherent impls are
+//! const fns, so that `guilty!(Struct::CONST)` can be used in const contexts, such as the
+//! initializers of statics (their values must then be const expressions). Functions in traits
+//! can't be const, so the consts of trait impls are not affected.
+//!
+//! The crate needs Rust 1.61 or later (for the runtime support of the `cached` option, and the
+//! proc macros the names are mangled with), and 1.70 or later with the `std` feature, whose `env`
+//! option uses `OnceLock`.
+//!
+//! Traits and impls accept options in `#[guilty(...)]` attributes, which must come before any
+//! other attributes:
+//!
+//! - `auto_layout` (impls): synthesize `SIZE` and `ALIGN` consts (of type `usize`) from the size
+//!   and alignment of the implementing type
+//! - `fingerprint` (traits and impls): the trait declares a `CONSTS_FINGERPRINT: u64` const, and
+//!   the impl defines it as a hash of the names, types and values of its consts, starting from a
+//!   hash of the names, types and defaults of the trait's consts (so that a changed default
+//!   changes the fingerprint of the impls which take it), for detecting when two builds disagree
+//!   about them. The values are hashed as written, by `stringify!`: a value computed from
+//!   something else (such as another const, or a `cfg!`) only changes the fingerprint when its
+//!   text changes, and the way `stringify!` spaces the tokens isn't guaranteed to stay the same
+//!   across compiler versions, so only compare fingerprints from builds by the same compiler
+//! - `inherit(Parent { const CONST: Type; ... })` (traits; may be repeated): declare the listed
+//!   consts of the supertrait `Parent` in the trait as well, with defaults that forward to
+//!   `Parent`, so that `guilty!(<Struct as Trait>::CONST)` finds them too (as the types of the
+//!   consts of another trait can't be looked up, they have to be written out). Since the trait
+//!   then has a `CONST` of its own, `guilty!(Struct::CONST)` is ambiguous where both traits are
+//!   in scope, and so is `guilty!(T::CONST)` with a `T: Trait` bound: use the qualified form. For
+//!   the same reason, the option should come after the options that list the consts (which would
+//!   access them through `Self`), as the consts are added when the option is reached.
+//! - `erased = Name` (traits): generate an object-safe trait `Name` whose `&self` methods return
+//!   the const values, implemented for every implementor of the trait, so that the consts can be
+//!   reached through a `dyn Name` (unless their types mention `Self`). With `Name` in scope, the
+//!   consts can also be accessed through any value whose type implements the trait, without
+//!   naming the type, as `guilty!(value.CONST)` (or `guilty!((expression).CONST)`)
+//! - `aggregates(max, min, sum)` (traits; any subset): add `CONSTS_MAX`, `CONSTS_MIN` and/or
+//!   `CONSTS_SUM` consts, whose defaults are computed from the values of all the other consts in
+//!   each impl (they must all have the same type). The trait needs at least one const, which
+//!   is not gated out by `cfg`, or it is a compile error.
+//! - `name_of` (traits): add a `fn name_of(value: &T) -> Option<&'static str>` method which finds
+//!   the name of the const equal to `value` in each impl (the consts must all have the same type)
+//! - `introspect` (traits): add methods for enumerating the consts at run time, say for
+//!   debugging or dumping a configuration. `Struct::__guilty_const_names()` returns the names of
+//!   the consts in the order they are declared, and `Struct::__guilty_describe(&mut visit)` calls
+//!   `visit(name, type, &value)` for each of them, with the type as written and the value of the
+//!   impl as a `&dyn Debug` (so the types must all implement `Debug`).
+//! - `track_overrides` (traits and impls): record which consts each impl defines itself, rather
+//!   than inheriting the trait's default. Then `guilty!(overridden Struct::CONST)` says whether
+//!   `Struct` defines `CONST`, and `guilty!(assert_overridden Trait::CONST for A, B, C)` panics if
+//!   any of the listed impls does not (handy in a test that lists every implementor).
+//! - `provenance` (traits and impls; `provenance` feature): record where the consts are defined.
+//!   Then `guilty!(location Struct::CONST)` is `Some((file, line))`, the location of the `guilty!`
+//!   call defining the impl, or the trait if the impl inherits the default. (The location of
+//!   each const within the call is not available to the macro.) Impls with the option require it
+//!   in the trait. Without the `provenance` feature, which is meant for debug builds, the location
+//!   is always `None`, so that the paths are left out of the binary.
+//! - `invariant(condition)` (traits; may be repeated): check that the condition holds for every
+//!   impl. The condition is a Rust expression (not a string) which accesses the consts through
+//!   `guilty!(Self::CONST)`, as in
+//!   `#[guilty(invariant(guilty!(Self::MIN) <= guilty!(Self::MAX)))]`. Since the consts are not
+//!   really consts, this can't be checked at compile time. Instead, the trait gets a
+//!   `fn assert_invariants()` method which panics with the condition that does not hold, to call
+//!   from a test listing the impls. Impls with the `check_invariants` option also check them
+//!   whenever one of their consts is accessed in a debug build.
+//! - `check_invariants` (impls): in a debug build, check the invariants of the trait (see
+//!   `invariant`) each time a const defined in the impl is accessed, and panic if one does not
+//!   hold. The trait must have invariants, and the type must be `Sized`. The consts the impl
+//!   inherits from the trait's defaults are not checked.
+//! - `inherent`, `inherent = pub`, `inherent = pub(crate)`, etc. (impls): mirror the consts
+//!   defined in the impl as inherent functions of the type, with the given visibility (private by
+//!   default), whatever the visibility of the trait. `guilty!(Struct::CONST)` then works without the
+//!   trait in scope, and is not ambiguous when several traits have a `CONST`. A const whose type
+//!   is one of the trait's associated types must spell out the type (`const EMPTY: u8`, not
+//!   `Self::Output`), since the inherent function can't name it through `Self`.
+//! - `unmangled` (traits and impls): name the functions generated for the consts and statics
+//!   after them, as in older versions, rather than `__guilty_const_NAME` (see above), for the
+//!   items added by the other options as well. The trait and its impls must agree: a trait with
+//!   the option is implemented by impls with it, and a trait which is not defined with `guilty!`
+//!   (which has methods named like the consts) by impls with it too. With the `real-consts`
+//!   feature, the option and `guilty!(unmangled ...)` are ignored.
+//! - `also_for_ref` (impls): also implement the trait for `&Struct`, forwarding the consts, statics,
+//!   associated types and methods to the impl for `Struct`. Use `also_for_ref(ref, mut, box)`, or
+//!   any subset, to choose which of `&Struct`, `&mut Struct` and `Box<Struct>` get impls. The
+//!   methods must have simple signatures: a `&self` or `&mut self` receiver (the latter is not
+//!   possible for `&Struct`) or none, arguments that are plain names, and no generics or where
+//!   clauses (any other method is a compile error). The attributes of the methods and associated
+//!   types, such as `cfg`s and docs, are copied onto the forwarding items. An impl with a
+//!   `&mut self` method has to leave out `ref`, as the method can't be called through `&Struct`:
+//!
+//!   ```compile_fail
+//!   # #[macro_use] extern crate guilt_by_association;
+//!   guilty! { trait Counter { const STEP: u32; fn bump(&mut self) -> u32; } }
+//!   struct Total(u32);
+//!   guilty! {
+//!       #[guilty(also_for_ref)] // error: `bump` can't be forwarded to `&Total`
+//!       impl Counter for Total {
+//!           const STEP: u32 = 1;
+//!           fn bump(&mut self) -> u32 { self.0 += 1; self.0 }
+//!       }
+//!   }
+//!   # fn main() {}
+//!   ```
+//! - `overridable` (impls; `std` feature): let tests temporarily replace the values of the consts
+//!   defined in the impl (they must be `Clone + 'static`). `guilty!(override Struct::CONST = value)`
+//!   installs an override for the current thread and returns a `ConstOverride` guard, which
+//!   removes it when dropped. Without the `std` feature the option has no effect.
+//! - `env = "VAR"` (consts in impls; `std` feature): put `#[guilty(env = "VAR")]` before a const
+//!   (and its other attributes) to look up its value in the environment variable `VAR` the first
+//!   time it is accessed. The value is parsed with `FromStr`, falling back to the value in the impl
+//!   if the variable is not set or does not parse. Without the `std` feature the option has no
+//!   effect.
+//! - `static` (consts in impls): put `#[guilty(static)]` before a const (and its other attributes)
+//!   to store its value in a static, which the function returns, rather than evaluating the value
+//!   on every access. This is meant for references, such as `&'static str` or `&'static [u8]`
+//!   consts with a value that is not a literal: every access returns the same reference. The type
+//!   must be `Copy`, and the value must be a const expression that does not mention `Self`, or the
+//!   generic parameters of the impl (which statics can't use). The option can't be combined with
+//!   `overridable`.
+//! - `cached` (consts in impls): put `#[guilty(cached)]` before a const of type `&'static T` (and
+//!   its other attributes) whose value is an expression of type `T`, such as a lookup table built
+//!   by a function, to evaluate it only once, on first access: the function returns a reference
+//!   to the stored value. (It works without std: any other threads accessing the const at the
+//!   same time wait for the first one.) As with `static`, `T` and the value can't mention `Self`
+//!   or the generic parameters of the impl (which is a compile error), so all the instantiations
+//!   of a generic impl share the one value, and the option can't be combined with `overridable`.
+//! - `fn_attr(attr, ...)` (consts in traits and impls): put `#[guilty(fn_attr(inline(always),
+//!   must_use))]` first among the attributes of a const (even before any other guilty option) to
+//!   put `#[inline(always)]` and `#[must_use]` on the function generated for it, after the other
+//!   attributes. Unlike those, they are left off everything else generated for the const by the
+//!   options (such as the real consts of `literals_mod` and the methods of the `erased` trait),
+//!   which may not accept them.
+//! - `impl_default` (consts in impls of traits): put `#[guilty(impl_default)]` before a const of
+//!   type `Self` (and its other attributes, but after `fn_attr` and `no_reflect`) to also
+//!   implement `Default` for the type, as `guilty!(<Struct as Trait>::CONST)`. The impl has the generics
+//!   and where clause of the impl of the trait, and any `cfg` attributes of the const.
+//! - `typenum = NAME` (consts in traits and impls of traits; `typenum` feature): put
+//!   `#[guilty(typenum = LEN_T)]` before an integer const (and its other attributes, but after
+//!   `fn_attr`) to also give the trait an associated type `LEN_T: typenum::Unsigned`, which the
+//!   impls set to the typenum type for the value of the const, as in `type LEN_T = U32;` for
+//!   `const LEN: usize = 32;`. This makes the value usable in type positions (such as the
+//!   lengths of `generic_array`s) without const generics. The crate using the option has to
+//!   depend on typenum itself (as `::typenum`, which in a 2015 edition crate means an
+//!   `extern crate typenum;` at its root). The value in each impl must be a plain integer literal,
+//!   from 0 through 256 or a power of two up to 65536, so every impl must define the const (and the
+//!   option can't be combined with `overridable`).
+//! - `per_variant` (consts in traits and impls): put `#[guilty(per_variant)]` before a const (and
+//!   its other attributes; it can't be combined with the other guilty options of consts) to give
+//!   each value of the type its own value of the const, typically one per enum variant. In an
+//!   impl, the value must be a `match self { ... }`, as in `const TAG: u8 = match self {
+//!   Message::Ping => 1, Message::Pong => 2 };`, and the trait must mark the const the same way.
+//!   The function generated for it is then a method taking `&self`, accessed as
+//!   `guilty!(message.TAG)` (or `guilty!((expression).TAG)`), and a default in the trait can't use
+//!   `self`. Such consts are left out of everything which lists the consts (as with `no_reflect`, and also `inherent` and
+//!   `aggregates`), their values are not rewritten by `self_consts` or `overridable`, and their
+//!   methods are object safe.
+//! - `no_reflect` (consts in traits and impls): put `#[guilty(no_reflect)]` before a const (and its
+//!   other attributes, including any other guilty option) to leave it out of everything which
+//!   lists the consts of a trait or impl: the `erased` trait, `name_of`, `introspect`, the
+//!   fingerprint, and the names recorded by `track_overrides` and `provenance`. This is meant for
+//!   sensitive values, such as keys.
+//! - `literals_mod = name` (impls): also define the consts of the impl whose values are literals
+//!   (integers, bools, strings...) as real consts, in a hidden module `name` next to the impl.
+//!   Unlike the functions, `guilty!(literal name::CONST)` can be used in const contexts, such as
+//!   array lengths and match patterns. Consts with the `env` option are left out, and as for
+//!   `inherent`, the consts typed by an associated type must spell out the type. The option also
+//!   defines a macro `name!`, for where the literal token itself is needed (as in
+//!   `concat!(name!(CONST), "...")`): `name!(CONST)` expands to the value as written. Like any
+//!   `macro_rules!` macro, it can be used after the impl in the same module (and its submodules),
+//!   and consts with attributes other than docs (such as `cfg`) are left out of it.
+//! - `object_safe` (traits): keep the trait object safe, so that it can still be used as
+//!   `dyn Trait`, by giving the functions generated for the consts a `where Self: Sized` bound.
+//!   The consts can then not be reached through the trait object (see `erased` for that). This
+//!   option is always applied after the others, and does not work together with `consts_mod`.
+//! - `self_consts` (traits and impls): rewrite `Self::CONST` in the bodies of the methods, and in
+//!   the values of the consts, into accesses of the consts, so that they can be written as if the
+//!   consts were real (as in `const DOUBLE: i32 = Self::BASE * 2;`).
+//!   Every path of the form `Self::NAME` which is not called, or continued by another `::`, is
+//!   taken to be a const, so associated types and enum variants must then be written otherwise
+//!   (as in `<Self as Trait>::Item`). Each body (and value) is walked one token at a time on its
+//!   own, so only a very long one may need a higher `#![recursion_limit]`.
+//! - `consts_mod = name` (traits and impls; must be the last option): generate the consts in a
+//!   trait of the same name in a hidden module `name`, which becomes a supertrait of the trait.
+//!   This keeps the trait's own items free of the generated functions, so they can't collide with
+//!   its methods: access the consts as `guilty!(<Struct as name::Trait>::CONST)`. The associated
+//!   types go along with the consts, since the types of the consts may mention them (the
+//!   methods can still use them as `Self::Type`, elsewhere as `<Struct as name::Trait>::Type`).
+
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+extern crate guilt_by_association_macros;
+// lets the proc-macro backend refer to the macro_rules engine from within the tests
+#[cfg(test)]
+extern crate self as guilt_by_association;
+
+// re-exported so that expansions can reach libcore from crates with or without std
+#[doc(hidden)]
+pub extern crate core as __core;
+
+// comparisons used by the aggregates option (which only requires PartialOrd)
+#[doc(hidden)]
+pub fn __max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a { b } else { a }
+}
+#[doc(hidden)]
+pub fn __min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a { b } else { a }
+}
+/// Folds one more const into an aggregate (there may be none so far, when consts are cfg-gated)
+///
+/// The consts of a trait with the aggregates option can't all be gated out, since there would be
+/// nothing to compute the aggregates from:
+///
+/// ```compile_fail
+/// #[macro_use] extern crate guilt_by_association;
+/// guilty! {
+///     #[guilty(aggregates(max))]
+///     trait Limits {
+///         #[cfg(any())] const SOFT: u32;
+///         #[cfg(any())] const HARD: u32;
+///     }
+/// }
+/// # fn main() {}
+/// ```
+#[doc(hidden)]
+pub fn __aggregate<T>(acc: Option<T>, value: T, combine: fn(T, T) -> T) -> Option<T> {
+    match acc {
+        Some(acc) => Some(combine(acc, value)),
+        None => Some(value),
+    }
+}
+
+// FNV-1a hash used by the fingerprint option
+#[doc(hidden)]
+pub fn __fingerprint(s: &str) -> u64 {
+    __fingerprint_extend(0xcbf29ce484222325, s)
+}
+#[doc(hidden)]
+pub fn __fingerprint_extend(hash: u64, s: &str) -> u64 {
+    s.bytes().fold(hash, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+// runs the invariants of a trait, as the consts of an impl are accessed in a debug build (the
+// invariants access consts themselves, so the check is skipped while one is already running)
+#[doc(hidden)]
+pub fn __check_invariants(check: fn()) {
+    use __core::sync::atomic::{AtomicBool, Ordering};
+
+    static CHECKING: AtomicBool = AtomicBool::new(false);
+
+    // lets the next access check again, even if this check panics
+    struct Reset;
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            CHECKING.store(false, Ordering::Release);
+        }
+    }
+
+    if !CHECKING.swap(true, Ordering::Acquire) {
+        let _reset = Reset;
+        check();
+    }
+}
+
+#[cfg(any(test, feature = "std"))]
+pub use overrides::{ConstOverride, __override_get};
+
+// runtime support for the overridable option
+#[cfg(any(test, feature = "std"))]
+mod overrides {
+    use std::any::{Any, TypeId};
+    use std::cell::RefCell;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Override {
+        id: usize,
+        ty: TypeId,
+        name: &'static str,
+        value: Box<dyn Any>,
+    }
+
+    thread_local! {
+        // the overrides currently installed on this thread, most recent last
+        static OVERRIDES: RefCell<Vec<Override>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Guard for a const value overridden with `guilty!(override Struct::CONST = value)`
+    ///
+    /// The override applies to the current thread only, and is removed when the guard is dropped.
+    /// Overrides may be nested: the most recently installed one wins.
+    #[must_use = "the override is removed as soon as the guard is dropped"]
+    pub struct ConstOverride {
+        id: usize,
+    }
+
+    impl ConstOverride {
+        #[doc(hidden)]
+        pub fn new<S: 'static, T: Clone + 'static>(_accessor: fn() -> T, name: &'static str, value: T) -> ConstOverride {
+            static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            OVERRIDES.with(|o| o.borrow_mut().push(Override { id, ty: TypeId::of::<S>(), name, value: Box::new(value) }));
+            ConstOverride { id }
+        }
+    }
+
+    impl Drop for ConstOverride {
+        fn drop(&mut self) {
+            OVERRIDES.with(|o| o.borrow_mut().retain(|o| o.id != self.id));
+        }
+    }
+
+    // looks up an override for a const, used by the overridable option
+    #[doc(hidden)]
+    pub fn __override_get<S: 'static, T: Clone + 'static>(name: &'static str) -> Option<T> {
+        OVERRIDES.with(|o| {
+            o.borrow().iter().rev()
+                .filter(|o| o.ty == TypeId::of::<S>() && o.name == name)
+                .filter_map(|o| o.value.downcast_ref::<T>())
+                .next()
+                .cloned()
+        })
+    }
+}
+
+#[cfg(not(any(test, feature = "std")))]
+#[doc(hidden)]
+pub fn __override_get<S: 'static, T: Clone + 'static>(_name: &'static str) -> Option<T> {
+    None
+}
+
+#[cfg(any(test, feature = "std"))]
+pub use env::{__EnvCache, __env_get};
+
+// runtime support for the env option (OnceLock is why the std feature needs Rust 1.70)
+#[cfg(any(test, feature = "std"))]
+#[clippy::msrv = "1.70"]
+mod env {
+    use std::str::FromStr;
+    use std::sync::OnceLock;
+
+    // the parsed value of an environment variable, read on first access
+    #[doc(hidden)]
+    pub struct __EnvCache<T>(OnceLock<Option<T>>);
+
+    impl<T> __EnvCache<T> {
+        #[doc(hidden)]
+        pub const fn new() -> __EnvCache<T> {
+            __EnvCache(OnceLock::new())
+        }
+    }
+
+    // looks up the value of a const in an environment variable, used by the env option
+    #[doc(hidden)]
+    pub fn __env_get<T: FromStr + Clone>(cache: &__EnvCache<T>, var: &str) -> Option<T> {
+        cache.0.get_or_init(|| std::env::var(var).ok().and_then(|value| value.parse().ok())).clone()
+    }
+}
+
+#[cfg(not(any(test, feature = "std")))]
+#[doc(hidden)]
+pub struct __EnvCache<T>(core::marker::PhantomData<T>);
+
+#[cfg(not(any(test, feature = "std")))]
+impl<T> __EnvCache<T> {
+    #[doc(hidden)]
+    pub const fn new() -> __EnvCache<T> {
+        __EnvCache(core::marker::PhantomData)
+    }
+}
+
+#[cfg(not(any(test, feature = "std")))]
+#[doc(hidden)]
+pub fn __env_get<T>(_cache: &__EnvCache<T>, _var: &str) -> Option<T> {
+    None
+}
+
+pub use cached::__Cached;
+
+// runtime support for the cached option (which works without std, so it can't use OnceLock)
+mod cached {
+    use __core::cell::UnsafeCell;
+    use __core::mem::MaybeUninit;
+    use __core::sync::atomic::{AtomicU8, Ordering};
+
+    const EMPTY: u8 = 0;
+    const BUSY: u8 = 1;
+    const READY: u8 = 2;
+
+    /// A value computed on first access, by one thread while any others wait for it
+    ///
+    /// The value of a cached const can't depend on the generic parameters of the impl, since the
+    /// one static is shared by all of its instantiations:
+    ///
+    /// ```compile_fail
+    /// #[macro_use] extern crate guilt_by_association;
+    /// guilty! { trait Size { const SIZE: &'static usize; } }
+    /// struct Wrapper<T>(T);
+    /// guilty! {
+    ///     impl<T> Size for Wrapper<T> {
+    ///         #[guilty(cached)] const SIZE: &'static usize = ::std::mem::size_of::<T>();
+    ///     }
+    /// }
+    /// # fn main() {}
+    /// ```
+    #[doc(hidden)]
+    pub struct __Cached<T> {
+        state: AtomicU8,
+        value: UnsafeCell<MaybeUninit<T>>,
+    }
+
+    unsafe impl<T: Send + Sync> Sync for __Cached<T> {}
+
+    // puts the cache back in the empty state if the initializer panics
+    struct Reset<'a>(&'a AtomicU8);
+
+    impl<'a> Drop for Reset<'a> {
+        fn drop(&mut self) {
+            self.0.store(EMPTY, Ordering::Release);
+        }
+    }
+
+    impl<T> __Cached<T> {
+        #[doc(hidden)]
+        pub const fn new() -> __Cached<T> {
+            __Cached { state: AtomicU8::new(EMPTY), value: UnsafeCell::new(MaybeUninit::uninit()) }
+        }
+
+        // returns the value, computing it with init if this is the first access
+        #[doc(hidden)]
+        pub fn get<F: FnOnce() -> T>(&self, init: F) -> &T {
+            loop {
+                match self.state.compare_exchange(EMPTY, BUSY, Ordering::Acquire, Ordering::Acquire) {
+                    Ok(_) => {
+                        let reset = Reset(&self.state);
+                        let value = init();
+                        // only the thread which moved the state to BUSY writes the value
+                        unsafe { (*self.value.get()).as_mut_ptr().write(value) };
+                        ::__core::mem::forget(reset);
+                        self.state.store(READY, Ordering::Release);
+                        break;
+                    }
+                    Err(READY) => break,
+                    Err(_) => ::__core::hint::spin_loop(),
+                }
+            }
+            // the state is READY, so the value is written and never changes again
+            unsafe { &*(*self.value.get()).as_ptr() }
+        }
+    }
+}
+
+// the location (file and line) of the guilty! call, recorded by the provenance option
+#[cfg(any(test, feature = "provenance"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guilty_location {
+    () => {
+        $crate::__core::option::Option::Some((file!(), line!()))
+    };
+}
+
+#[cfg(not(any(test, feature = "provenance")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guilty_location {
+    () => {
+        $crate::__core::option::Option::None
+    };
+}
+
+// the typenum type for the value of a const with the typenum option (a plain integer literal),
+// from the unsigned integers that typenum names: 0 through 256, and the powers of two up to 65536
+#[cfg(any(test, feature = "typenum"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guilty_typenum {
+    (0) => { ::typenum::consts::U0 }; (1) => { ::typenum::consts::U1 }; (2) => { ::typenum::consts::U2 };
+    (3) => { ::typenum::consts::U3 }; (4) => { ::typenum::consts::U4 }; (5) => { ::typenum::consts::U5 };
+    (6) => { ::typenum::consts::U6 }; (7) => { ::typenum::consts::U7 }; (8) => { ::typenum::consts::U8 };
+    (9) => { ::typenum::consts::U9 }; (10) => { ::typenum::consts::U10 }; (11) => { ::typenum::consts::U11 };
+    (12) => { ::typenum::consts::U12 }; (13) => { ::typenum::consts::U13 }; (14) => { ::typenum::consts::U14 };
+    (15) => { ::typenum::consts::U15 }; (16) => { ::typenum::consts::U16 }; (17) => { ::typenum::consts::U17 };
+    (18) => { ::typenum::consts::U18 }; (19) => { ::typenum::consts::U19 }; (20) => { ::typenum::consts::U20 };
+    (21) => { ::typenum::consts::U21 }; (22) => { ::typenum::consts::U22 }; (23) => { ::typenum::consts::U23 };
+    (24) => { ::typenum::consts::U24 }; (25) => { ::typenum::consts::U25 }; (26) => { ::typenum::consts::U26 };
+    (27) => { ::typenum::consts::U27 }; (28) => { ::typenum::consts::U28 }; (29) => { ::typenum::consts::U29 };
+    (30) => { ::typenum::consts::U30 }; (31) => { ::typenum::consts::U31 }; (32) => { ::typenum::consts::U32 };
+    (33) => { ::typenum::consts::U33 }; (34) => { ::typenum::consts::U34 }; (35) => { ::typenum::consts::U35 };
+    (36) => { ::typenum::consts::U36 }; (37) => { ::typenum::consts::U37 }; (38) => { ::typenum::consts::U38 };
+    (39) => { ::typenum::consts::U39 }; (40) => { ::typenum::consts::U40 }; (41) => { ::typenum::consts::U41 };
+    (42) => { ::typenum::consts::U42 }; (43) => { ::typenum::consts::U43 }; (44) => { ::typenum::consts::U44 };
+    (45) => { ::typenum::consts::U45 }; (46) => { ::typenum::consts::U46 }; (47) => { ::typenum::consts::U47 };
+    (48) => { ::typenum::consts::U48 }; (49) => { ::typenum::consts::U49 }; (50) => { ::typenum::consts::U50 };
+    (51) => { ::typenum::consts::U51 }; (52) => { ::typenum::consts::U52 }; (53) => { ::typenum::consts::U53 };
+    (54) => { ::typenum::consts::U54 }; (55) => { ::typenum::consts::U55 }; (56) => { ::typenum::consts::U56 };
+    (57) => { ::typenum::consts::U57 }; (58) => { ::typenum::consts::U58 }; (59) => { ::typenum::consts::U59 };
+    (60) => { ::typenum::consts::U60 }; (61) => { ::typenum::consts::U61 }; (62) => { ::typenum::consts::U62 };
+    (63) => { ::typenum::consts::U63 }; (64) => { ::typenum::consts::U64 }; (65) => { ::typenum::consts::U65 };
+    (66) => { ::typenum::consts::U66 }; (67) => { ::typenum::consts::U67 }; (68) => { ::typenum::consts::U68 };
+    (69) => { ::typenum::consts::U69 }; (70) => { ::typenum::consts::U70 }; (71) => { ::typenum::consts::U71 };
+    (72) => { ::typenum::consts::U72 }; (73) => { ::typenum::consts::U73 }; (74) => { ::typenum::consts::U74 };
+    (75) => { ::typenum::consts::U75 }; (76) => { ::typenum::consts::U76 }; (77) => { ::typenum::consts::U77 };
+    (78) => { ::typenum::consts::U78 }; (79) => { ::typenum::consts::U79 }; (80) => { ::typenum::consts::U80 };
+    (81) => { ::typenum::consts::U81 }; (82) => { ::typenum::consts::U82 }; (83) => { ::typenum::consts::U83 };
+    (84) => { ::typenum::consts::U84 }; (85) => { ::typenum::consts::U85 }; (86) => { ::typenum::consts::U86 };
+    (87) => { ::typenum::consts::U87 }; (88) => { ::typenum::consts::U88 }; (89) => { ::typenum::consts::U89 };
+    (90) => { ::typenum::consts::U90 }; (91) => { ::typenum::consts::U91 }; (92) => { ::typenum::consts::U92 };
+    (93) => { ::typenum::consts::U93 }; (94) => { ::typenum::consts::U94 }; (95) => { ::typenum::consts::U95 };
+    (96) => { ::typenum::consts::U96 }; (97) => { ::typenum::consts::U97 }; (98) => { ::typenum::consts::U98 };
+    (99) => { ::typenum::consts::U99 }; (100) => { ::typenum::consts::U100 }; (101) => { ::typenum::consts::U101 };
+    (102) => { ::typenum::consts::U102 }; (103) => { ::typenum::consts::U103 }; (104) => { ::typenum::consts::U104 };
+    (105) => { ::typenum::consts::U105 }; (106) => { ::typenum::consts::U106 }; (107) => { ::typenum::consts::U107 };
+    (108) => { ::typenum::consts::U108 }; (109) => { ::typenum::consts::U109 }; (110) => { ::typenum::consts::U110 };
+    (111) => { ::typenum::consts::U111 }; (112) => { ::typenum::consts::U112 }; (113) => { ::typenum::consts::U113 };
+    (114) => { ::typenum::consts::U114 }; (115) => { ::typenum::consts::U115 }; (116) => { ::typenum::consts::U116 };
+    (117) => { ::typenum::consts::U117 }; (118) => { ::typenum::consts::U118 }; (119) => { ::typenum::consts::U119 };
+    (120) => { ::typenum::consts::U120 }; (121) => { ::typenum::consts::U121 }; (122) => { ::typenum::consts::U122 };
+    (123) => { ::typenum::consts::U123 }; (124) => { ::typenum::consts::U124 }; (125) => { ::typenum::consts::U125 };
+    (126) => { ::typenum::consts::U126 }; (127) => { ::typenum::consts::U127 }; (128) => { ::typenum::consts::U128 };
+    (129) => { ::typenum::consts::U129 }; (130) => { ::typenum::consts::U130 }; (131) => { ::typenum::consts::U131 };
+    (132) => { ::typenum::consts::U132 }; (133) => { ::typenum::consts::U133 }; (134) => { ::typenum::consts::U134 };
+    (135) => { ::typenum::consts::U135 }; (136) => { ::typenum::consts::U136 }; (137) => { ::typenum::consts::U137 };
+    (138) => { ::typenum::consts::U138 }; (139) => { ::typenum::consts::U139 }; (140) => { ::typenum::consts::U140 };
+    (141) => { ::typenum::consts::U141 }; (142) => { ::typenum::consts::U142 }; (143) => { ::typenum::consts::U143 };
+    (144) => { ::typenum::consts::U144 }; (145) => { ::typenum::consts::U145 }; (146) => { ::typenum::consts::U146 };
+    (147) => { ::typenum::consts::U147 }; (148) => { ::typenum::consts::U148 }; (149) => { ::typenum::consts::U149 };
+    (150) => { ::typenum::consts::U150 }; (151) => { ::typenum::consts::U151 }; (152) => { ::typenum::consts::U152 };
+    (153) => { ::typenum::consts::U153 }; (154) => { ::typenum::consts::U154 }; (155) => { ::typenum::consts::U155 };
+    (156) => { ::typenum::consts::U156 }; (157) => { ::typenum::consts::U157 }; (158) => { ::typenum::consts::U158 };
+    (159) => { ::typenum::consts::U159 }; (160) => { ::typenum::consts::U160 }; (161) => { ::typenum::consts::U161 };
+    (162) => { ::typenum::consts::U162 }; (163) => { ::typenum::consts::U163 }; (164) => { ::typenum::consts::U164 };
+    (165) => { ::typenum::consts::U165 }; (166) => { ::typenum::consts::U166 }; (167) => { ::typenum::consts::U167 };
+    (168) => { ::typenum::consts::U168 }; (169) => { ::typenum::consts::U169 }; (170) => { ::typenum::consts::U170 };
+    (171) => { ::typenum::consts::U171 }; (172) => { ::typenum::consts::U172 }; (173) => { ::typenum::consts::U173 };
+    (174) => { ::typenum::consts::U174 }; (175) => { ::typenum::consts::U175 }; (176) => { ::typenum::consts::U176 };
+    (177) => { ::typenum::consts::U177 }; (178) => { ::typenum::consts::U178 }; (179) => { ::typenum::consts::U179 };
+    (180) => { ::typenum::consts::U180 }; (181) => { ::typenum::consts::U181 }; (182) => { ::typenum::consts::U182 };
+    (183) => { ::typenum::consts::U183 }; (184) => { ::typenum::consts::U184 }; (185) => { ::typenum::consts::U185 };
+    (186) => { ::typenum::consts::U186 }; (187) => { ::typenum::consts::U187 }; (188) => { ::typenum::consts::U188 };
+    (189) => { ::typenum::consts::U189 }; (190) => { ::typenum::consts::U190 }; (191) => { ::typenum::consts::U191 };
+    (192) => { ::typenum::consts::U192 }; (193) => { ::typenum::consts::U193 }; (194) => { ::typenum::consts::U194 };
+    (195) => { ::typenum::consts::U195 }; (196) => { ::typenum::consts::U196 }; (197) => { ::typenum::consts::U197 };
+    (198) => { ::typenum::consts::U198 }; (199) => { ::typenum::consts::U199 }; (200) => { ::typenum::consts::U200 };
+    (201) => { ::typenum::consts::U201 }; (202) => { ::typenum::consts::U202 }; (203) => { ::typenum::consts::U203 };
+    (204) => { ::typenum::consts::U204 }; (205) => { ::typenum::consts::U205 }; (206) => { ::typenum::consts::U206 };
+    (207) => { ::typenum::consts::U207 }; (208) => { ::typenum::consts::U208 }; (209) => { ::typenum::consts::U209 };
+    (210) => { ::typenum::consts::U210 }; (211) => { ::typenum::consts::U211 }; (212) => { ::typenum::consts::U212 };
+    (213) => { ::typenum::consts::U213 }; (214) => { ::typenum::consts::U214 }; (215) => { ::typenum::consts::U215 };
+    (216) => { ::typenum::consts::U216 }; (217) => { ::typenum::consts::U217 }; (218) => { ::typenum::consts::U218 };
+    (219) => { ::typenum::consts::U219 }; (220) => { ::typenum::consts::U220 }; (221) => { ::typenum::consts::U221 };
+    (222) => { ::typenum::consts::U222 }; (223) => { ::typenum::consts::U223 }; (224) => { ::typenum::consts::U224 };
+    (225) => { ::typenum::consts::U225 }; (226) => { ::typenum::consts::U226 }; (227) => { ::typenum::consts::U227 };
+    (228) => { ::typenum::consts::U228 }; (229) => { ::typenum::consts::U229 }; (230) => { ::typenum::consts::U230 };
+    (231) => { ::typenum::consts::U231 }; (232) => { ::typenum::consts::U232 }; (233) => { ::typenum::consts::U233 };
+    (234) => { ::typenum::consts::U234 }; (235) => { ::typenum::consts::U235 }; (236) => { ::typenum::consts::U236 };
+    (237) => { ::typenum::consts::U237 }; (238) => { ::typenum::consts::U238 }; (239) => { ::typenum::consts::U239 };
+    (240) => { ::typenum::consts::U240 }; (241) => { ::typenum::consts::U241 }; (242) => { ::typenum::consts::U242 };
+    (243) => { ::typenum::consts::U243 }; (244) => { ::typenum::consts::U244 }; (245) => { ::typenum::consts::U245 };
+    (246) => { ::typenum::consts::U246 }; (247) => { ::typenum::consts::U247 }; (248) => { ::typenum::consts::U248 };
+    (249) => { ::typenum::consts::U249 }; (250) => { ::typenum::consts::U250 }; (251) => { ::typenum::consts::U251 };
+    (252) => { ::typenum::consts::U252 }; (253) => { ::typenum::consts::U253 }; (254) => { ::typenum::consts::U254 };
+    (255) => { ::typenum::consts::U255 }; (256) => { ::typenum::consts::U256 }; (512) => { ::typenum::consts::U512 };
+    (1024) => { ::typenum::consts::U1024 }; (2048) => { ::typenum::consts::U2048 }; (4096) => { ::typenum::consts::U4096 };
+    (8192) => { ::typenum::consts::U8192 }; (16384) => { ::typenum::consts::U16384 }; (32768) => { ::typenum::consts::U32768 };
+    (65536) => { ::typenum::consts::U65536 };
+    ($value:tt) => {
+        compile_error!(concat!("guilty!: no typenum type for `", stringify!($value),
+                               "` (the value must be an integer literal without a suffix, from 0 through 256 or a power of two up to 65536)"))
+    };
+}
+
+#[cfg(not(any(test, feature = "typenum")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guilty_typenum {
+    ($value:tt) => {
+        compile_error!("guilty!: the typenum option needs the typenum feature")
+    };
+}
+
+// the function generated for a const of an inherent impl, which is a const fn with the const-fn
+// feature
+#[cfg(any(test, feature = "const-fn"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guilty_inherent_fn {
+    ([$($cattr:tt)*] $vis:vis $constname:ident : $consttype:ty = $constvalue:expr) => {
+        $($cattr)* #[allow(non_snake_case)] $vis const fn $constname() -> $consttype { $constvalue }
+    };
+}
+
+#[cfg(not(any(test, feature = "const-fn")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guilty_inherent_fn {
+    ([$($cattr:tt)*] $vis:vis $constname:ident : $consttype:ty = $constvalue:expr) => {
+        $($cattr)* #[allow(non_snake_case)] $vis fn $constname() -> $consttype { $constvalue }
+    };
+}
+
+/// Macro for declaring/implementing traits with fake associated consts
+///
+/// See the [crate-level documentation](index.html) for more.
+#[cfg(not(any(feature = "proc-macro", feature = "real-consts")))]
+#[macro_export]
+macro_rules! guilty {
+    ($($input:tt)*) => {
+        $crate::__guilty_rules! { $($input)* }
+    };
+}
+
+/// Macro for declaring/implementing traits with associated consts
+///
+/// See the [crate-level documentation](index.html) for more.
+#[cfg(all(feature = "real-consts", not(feature = "proc-macro")))]
+#[macro_export]
+macro_rules! guilty {
+    ($($input:tt)*) => {
+        $crate::__guilty_real! { $($input)* }
+    };
+}
+
+#[cfg(feature = "proc-macro")]
+#[doc(inline)]
+pub use guilt_by_association_macros::guilty;
+
+#[cfg(feature = "proc-macro")]
+#[doc(inline)]
+pub use guilt_by_association_macros::guilty_item;
+
+// names the functions generated for the consts, see the access arm of the engine
+#[doc(hidden)]
+pub use guilt_by_association_macros::__guilty_mangle;
+
+// The macro_rules engine behind guilty!. It is always available, because the proc-macro backend
+// hands anything it does not rewrite itself over to it.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guilty_rules {
+    // These are the user facing invocations. They are ordered so that the most common shapes are
+    // matched first, without trying (and failing) many other arms: const accesses, then (after
+    // guilty options, which must be peeled off before anything matches other attributes)
+    // traits/impls consisting only of undecorated consts, then the general forms.
+
+    // 1a. access a const declared with this macro (w/o mentioning trait)
+    ($structname:ident :: $constname:ident) => {
+        $crate::__guilty_rules!(INTERNAL: ACCESS CONST, ($structname), $constname)
+    };
+    // 1b. access a const declared with this macro (mentioning trait, or just wrapping the type in
+    //     angle brackets, as in <Vec<u8>>::CONST)
+    //     the type may itself be a qualified path, as in <<T as Trait>::Assoc as Trait>::CONST,
+    //     whose leading << is split for the parser
+    (<$structname:ty as $traitname:path> :: $constname:ident) => {
+        $crate::__guilty_rules!(INTERNAL: ACCESS CONST, (<$structname as $traitname>), $constname)
+    };
+    (<$structname:ty> :: $constname:ident) => {
+        $crate::__guilty_rules!(INTERNAL: ACCESS CONST, (<$structname>), $constname)
+    };
+    (<< $($path:tt)+) => {
+        $crate::__guilty_rules!(< < $($path)+)
+    };
+    // 1c. access a const declared with this macro, with the type and const name as separate
+    //     fragments (for use by other macros, which may have built or captured them separately)
+    (const $structname:ty, $constname:ident) => {
+        $crate::__guilty_rules!(INTERNAL: ACCESS CONST, (<$structname>), $constname)
+    };
+    // 1d. same as 1c, mentioning trait
+    (const $structname:ty as $traitname:path, $constname:ident) => {
+        $crate::__guilty_rules!(INTERNAL: ACCESS CONST, (<$structname as $traitname>), $constname)
+    };
+    // 1e. check whether an impl defines a const itself, rather than inheriting the default (only
+    //     for traits and impls with the track_overrides option)
+    (overridden <$structname:ty as $traitname:path> :: $constname:ident) => {
+        $crate::__guilty_rules!(INTERNAL: CHECK OVERRIDDEN, (<$structname as $traitname>), $constname)
+    };
+    (overridden $structname:ident :: $constname:ident) => {
+        $crate::__guilty_rules!(INTERNAL: CHECK OVERRIDDEN, ($structname), $constname)
+    };
+    // 1f. assert that each of a list of impls defines a const itself
+    (assert_overridden $traitname:ident :: $constname:ident for $($structname:ident),+) => {{
+        $(assert!($crate::__guilty_rules!(overridden <$structname as $traitname>::$constname),
+                  concat!(stringify!($structname), " inherits the default value of ",
+                          stringify!($traitname), "::", stringify!($constname)));)+
+    }};
+    // 1g. temporarily override a const (only for impls with the overridable option)
+    (override <$structname:ty as $traitname:path> :: $constname:ident = $value:expr) => {
+        $crate::__guilty_rules!(INTERNAL: OVERRIDE CONST, $structname, (<$structname as $traitname>), $constname, $value)
+    };
+    (override $structname:ident :: $constname:ident = $value:expr) => {
+        $crate::__guilty_rules!(INTERNAL: OVERRIDE CONST, $structname, ($structname), $constname, $value)
+    };
+    // 1h. find where a const is defined (only for traits and impls with the provenance option)
+    (location <$structname:ty as $traitname:path> :: $constname:ident) => {
+        $crate::__guilty_rules!(INTERNAL: LOCATE CONST, (<$structname as $traitname>), $constname)
+    };
+    (location $structname:ident :: $constname:ident) => {
+        $crate::__guilty_rules!(INTERNAL: LOCATE CONST, ($structname), $constname)
+    };
+    // 1i. access the real const generated for an impl with the literals_mod option
+    (literal $m:ident :: $constname:ident) => {
+        $m::$constname
+    };
+    // 1i'. access a const of a trait or impl with the unmangled option, through any path (or a
+    //     value, as in 1k)
+    (unmangled $($path:tt)+) => {{
+        $($path)+ ()
+    }};
+    // 1j. access a const through a longer path, as in module::Struct::CONST, Struct::<T>::CONST,
+    //     crate::module::Struct::CONST or ::other_crate::Struct::CONST (one token tree at a time,
+    //     see access-path)
+    //     an impl of a trait named by a path with a leading ::, which would also match, is passed
+    //     on to 4d first
+    (impl :: $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM, [], impl :: $($item)+);
+    };
+    ($first:ident :: $($path:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ACCESS PATH, [$first ::], $($path)+)
+    };
+    (:: $first:ident :: $($path:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ACCESS PATH, [:: $first ::], $($path)+)
+    };
+    // 1k. access a const through a value (a name, or an expression in parentheses), with the
+    //     methods of the companion trait (only for traits with the erased option)
+    ($value:tt . $constname:ident) => {
+        $crate::__guilty_mangle!([] $value.__guilty_mangle $constname())
+    };
+    // 2. define or implement a trait with guilty options
+    (#[guilty($($opt:tt)*)] $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM, [$($opt)* ,], $($item)+);
+    };
+    // 3a. fast path: implement a trait with nothing but consts, whose values are literals
+    (impl $traitname:ident for $structname:ident
+     {
+         $(const $constname:ident : $consttype:ty = $constvalue:literal;)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: AS ITEM,
+                impl $traitname for $structname {
+                    $(
+                        #[doc = concat!("Value: `", stringify!($constvalue), "`")]
+                        #[allow(non_snake_case)] fn __guilty_mangle $constname() -> $consttype { $constvalue }
+                    )*
+                });
+    };
+    // 3b. fast path: define a private trait with nothing but consts without defaults
+    ($(#[$attr:meta])* trait $traitname:ident
+     {
+         $(const $constname:ident : $consttype:ty;)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: AS ITEM,
+                $(#[$attr])* trait $traitname {
+                    $(#[allow(non_snake_case)] fn __guilty_mangle $constname() -> $consttype;)*
+                });
+    };
+    // 3c. fast path: define a public trait with nothing but consts without defaults
+    ($(#[$attr:meta])* pub trait $traitname:ident
+     {
+         $(const $constname:ident : $consttype:ty;)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: AS ITEM,
+                $(#[$attr])* pub trait $traitname {
+                    $(#[allow(non_snake_case)] fn __guilty_mangle $constname() -> $consttype;)*
+                });
+    };
+    // 4a. define a private trait
+    ($(#[$attr:meta])* trait $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM, [], $(#[$attr])* trait $($item)+);
+    };
+    // 4b. define a public or restricted trait
+    ($(#[$attr:meta])* pub $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM, [], $(#[$attr])* pub $($item)+);
+    };
+    // 4c. implement a trait for arrays [T; 0] through [T; 32], with a LEN const
+    (impl $traitname:ident for [$elem:ident; 0..=32] $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ARRAY IMPL, [$traitname $elem], [], $($rest)+);
+    };
+    // 4d. implement a trait (public or private), or write an inherent impl
+    ($(#[$($attr:tt)*])* impl $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM, [], $(#[$($attr)*])* impl $($item)+);
+    };
+    // 4e. define an unsafe trait, or implement one
+    ($(#[$($attr:tt)*])* unsafe $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM, [], $(#[$($attr)*])* unsafe $($item)+);
+    };
+    // 4f. define an extension trait of another (say, foreign) trait
+    ($(#[$attr:meta])* extend $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM, [], $(#[$attr])* extend $($item)+);
+    };
+    // 4g. any other item is passed through, as are any items after it (see more-items)
+    ($(#[$($attr:tt)*])* $keyword:ident $name:ident $($item:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: [], $(#[$($attr)*])* $keyword $name $($item)*);
+    };
+
+    // Following are the internal macro calls
+    // Since you can't export a macro which calls other unexported macros, the engine calls itself
+    // recursively in order to continue parsing. The invocation syntax for all these recursive
+    // calls starts with the tokens `INTERNAL:`.
+    //
+    // The general strategy for parsing these declarations is we parse one item from the beginning
+    // of the trait/impl at a time, and append it to an accumulator in square brackets, turning
+    // consts into static functions on the way. When all of the items have been seen, the recursion
+    // stops and the trait/impl is outputted (with an indirection through AS ITEM to appease the
+    // parser). Each step only looks at the item in front, so the recursion depth grows with the
+    // number of items (not their length, where possible), and whole bodies that need no changes
+    // are matched in one go.
+
+
+    // item-opt: collect guilty options from any further #[guilty(...)] attributes
+    // options are kept as a comma-terminated list in the square brackets
+    (INTERNAL: ITEM, [$($opts:tt)*], #[guilty($($opt:tt)*)] $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM, [$($opts)* $($opt)* ,], $($item)+);
+    };
+    // item-extend: an extension trait is a trait with the other trait as its supertrait, followed
+    //    by the body, so that it is parsed like any other trait
+    (INTERNAL: ITEM, $opts:tt, $(#[$attr:meta])* extend trait $foreign:path as $traitname:ident $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT HEADER, $opts, [$(#[$attr])*] [trait] [$traitname : $foreign], $($rest)+);
+    };
+    (INTERNAL: ITEM, $opts:tt, $(#[$attr:meta])* pub extend trait $foreign:path as $traitname:ident $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT HEADER, $opts, [$(#[$attr])*] [pub trait] [$traitname : $foreign], $($rest)+);
+    };
+    (INTERNAL: ITEM, $opts:tt, $(#[$attr:meta])* pub ($($restr:tt)+) extend trait $foreign:path as $traitname:ident $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT HEADER, $opts, [$(#[$attr])*] [pub ($($restr)+) trait] [$traitname : $foreign],
+                        $($rest)+);
+    };
+    (INTERNAL: ITEM, $opts:tt, $(#[$attr:meta])* extend $($rest:tt)*) => {
+        compile_error!("guilty!: expected `extend trait path::to::Trait as NewTrait { ... }`");
+    };
+    (INTERNAL: ITEM, $opts:tt, $(#[$attr:meta])* pub extend $($rest:tt)*) => {
+        compile_error!("guilty!: expected `pub extend trait path::to::Trait as NewTrait { ... }`");
+    };
+    (INTERNAL: ITEM, $opts:tt, $(#[$attr:meta])* pub ($($restr:tt)+) extend $($rest:tt)*) => {
+        compile_error!("guilty!: expected `pub(...) extend trait path::to::Trait as NewTrait { ... }`");
+    };
+    // item-trait: parse the header of a private trait
+    (INTERNAL: ITEM, $opts:tt, $(#[$attr:meta])* trait $traitname:ident $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT HEADER, $opts, [$(#[$attr])*] [trait] [$traitname], $($rest)+);
+    };
+    // item-pub-trait: parse the header of a public trait
+    (INTERNAL: ITEM, $opts:tt, $(#[$attr:meta])* pub trait $traitname:ident $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT HEADER, $opts, [$(#[$attr])*] [pub trait] [$traitname], $($rest)+);
+    };
+    // item-restr-trait: parse the header of a public restricted trait: the restriction may be
+    //    anything in parentheses, such as `(crate)`, `(self)`, `(super)` or `(in some::path)`
+    (INTERNAL: ITEM, $opts:tt, $(#[$attr:meta])* pub ($($restr:tt)+) trait $traitname:ident $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT HEADER, $opts, [$(#[$attr])*] [pub ($($restr)+) trait] [$traitname], $($rest)+);
+    };
+    // item-unsafe-trait: parse the header of an unsafe trait
+    (INTERNAL: ITEM, $opts:tt, $(#[$attr:meta])* unsafe trait $traitname:ident $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT HEADER, $opts, [$(#[$attr])*] [unsafe trait] [$traitname], $($rest)+);
+    };
+    (INTERNAL: ITEM, $opts:tt, $(#[$attr:meta])* pub unsafe trait $traitname:ident $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT HEADER, $opts, [$(#[$attr])*] [pub unsafe trait] [$traitname], $($rest)+);
+    };
+    (INTERNAL: ITEM, $opts:tt, $(#[$attr:meta])* pub ($($restr:tt)+) unsafe trait $traitname:ident $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT HEADER, $opts, [$(#[$attr])*] [pub ($($restr)+) unsafe trait] [$traitname],
+                        $($rest)+);
+    };
+    // trait-header-lifetimes: add the lifetime parameters to the trait name
+    (INTERNAL: TRAIT HEADER, $opts:tt, $attrs:tt $before:tt [$($traitname:tt)*], <$($lt:lifetime),+> $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT HEADER, $opts, $attrs $before [$($traitname)* <$($lt),+>], $($rest)+);
+    };
+    (INTERNAL: TRAIT HEADER, $opts:tt, $attrs:tt $before:tt [$($traitname:tt)*], < $($rest:tt)+) => {
+        compile_error!(concat!("guilty!: trait `", stringify!($($traitname)*),
+                               "` may only have lifetime parameters, generic type parameters are not supported"));
+    };
+    // trait-header-bounds: add the supertraits and/or the where clause to the trait name, one
+    //    token tree at a time
+    (INTERNAL: TRAIT HEADER, $opts:tt, $attrs:tt $before:tt [$($traitname:tt)*], : $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BOUNDS, $opts, $attrs $before [$($traitname)* :], $($rest)+);
+    };
+    (INTERNAL: TRAIT HEADER, $opts:tt, $attrs:tt $before:tt [$($traitname:tt)*], where $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BOUNDS, $opts, $attrs $before [$($traitname)* where], $($rest)+);
+    };
+    (INTERNAL: TRAIT BOUNDS, $opts:tt, $attrs:tt $before:tt $traitname:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS, [TRAIT OPTIONS, $opts, $attrs $before $traitname,], $body);
+    };
+    (INTERNAL: TRAIT BOUNDS, $opts:tt, $attrs:tt $before:tt $traitname:tt, { $($body:tt)* } $next:ident $($rest:tt)*) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [TRAIT BOUNDS, $opts, $attrs $before $traitname, { $($body)* }], $next $($rest)*);
+    };
+    (INTERNAL: TRAIT BOUNDS, $opts:tt, $attrs:tt $before:tt $traitname:tt, { $($body:tt)* } # $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [TRAIT BOUNDS, $opts, $attrs $before $traitname, { $($body)* }], # $($rest)+);
+    };
+    (INTERNAL: TRAIT BOUNDS, $opts:tt, $attrs:tt $before:tt [$($traitname:tt)*], $next:tt $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BOUNDS, $opts, $attrs $before [$($traitname)* $next], $($rest)+);
+    };
+    // trait-header-done: go on to the options (via separators)
+    (INTERNAL: TRAIT HEADER, $opts:tt, $attrs:tt $before:tt $traitname:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS, [TRAIT OPTIONS, $opts, $attrs $before $traitname,], $body);
+    };
+    (INTERNAL: TRAIT HEADER, $opts:tt, $attrs:tt $before:tt $traitname:tt, { $($body:tt)* } $next:ident $($rest:tt)*) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [TRAIT HEADER, $opts, $attrs $before $traitname, { $($body)* }], $next $($rest)*);
+    };
+    (INTERNAL: TRAIT HEADER, $opts:tt, $attrs:tt $before:tt $traitname:tt, { $($body:tt)* } # $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [TRAIT HEADER, $opts, $attrs $before $traitname, { $($body)* }], # $($rest)+);
+    };
+    // more-items: the item is followed by more items (after its body, the next token starts an item
+    //    rather than continuing a generic argument, as in Trait<{ N }>), so output it on its own,
+    //    then split the others (see guilty_mod)
+    (INTERNAL: MORE ITEMS, [$($item:tt)*], $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: $($item)*);
+        $crate::guilty_mod!(INTERNAL: [], $($rest)+);
+    };
+    // item-attrs: collect the attributes in front of an impl (traits are taken care of above), any
+    //    cfg attribute goes on the rest of the expansion so that it applies to everything generated
+    (INTERNAL: ITEM, $opts:tt, #[$($attr:tt)*] $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM ATTRS, $opts, [], #[$($attr)*] $($item)+);
+    };
+    (INTERNAL: ITEM ATTRS, [$($opts:tt)*], $attrs:tt, #[guilty($($opt:tt)*)] $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM ATTRS, [$($opts)* $($opt)* ,], $attrs, $($item)+);
+    };
+    // the impl is split off from any items after it first, which the cfg attribute is not for
+    (INTERNAL: ITEM ATTRS, $opts:tt, $attrs:tt, #[cfg $pred:tt] $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ITEM, [$opts, $attrs, $pred], [], $($item)+);
+    };
+    (INTERNAL: CFG ITEM, [$opts:tt, $attrs:tt, $pred:tt], [$($item:tt)*], { $($body:tt)* }) => {
+        #[cfg $pred] $crate::__guilty_rules!(INTERNAL: ITEM ATTRS, $opts, $attrs, $($item)* { $($body)* });
+    };
+    (INTERNAL: CFG ITEM, $info:tt, $item:tt, { $($body:tt)* } $next:ident $($rest:tt)*) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [CFG ITEM, $info, $item, { $($body)* }], $next $($rest)*);
+    };
+    (INTERNAL: CFG ITEM, $info:tt, $item:tt, { $($body:tt)* } # $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [CFG ITEM, $info, $item, { $($body)* }], # $($rest)+);
+    };
+    (INTERNAL: CFG ITEM, $info:tt, [$($item:tt)*], $next:tt $($rest:tt)*) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ITEM, $info, [$($item)* $next], $($rest)*);
+    };
+    (INTERNAL: ITEM ATTRS, $opts:tt, [$($attrs:tt)*], #[$($attr:tt)*] $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM ATTRS, $opts, [$($attrs)* #[$($attr)*]], $($item)+);
+    };
+    // item-attrs-done: the other attributes go at the end of the options, where impl-opt-done puts
+    //    them back on the impl
+    (INTERNAL: ITEM ATTRS, [$($opts:tt)*], [$($attrs:tt)*], impl $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM, [$($opts)* $($attrs)*], impl $($item)+);
+    };
+    (INTERNAL: ITEM ATTRS, [$($opts:tt)*], [$($attrs:tt)*], unsafe impl $($item:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM, [$($opts)* $($attrs)*], unsafe impl $($item)+);
+    };
+    // item-impl: parse the header of an impl, one token tree at a time, into the generics (without
+    //    the angle brackets), the trait, the type and the where clause
+    // the options see the impl header as [[generics] [trait] [type] [where]]
+    (INTERNAL: ITEM, $opts:tt, impl < $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL GENERICS, $opts, [], [], $($rest)+);
+    };
+    (INTERNAL: ITEM, $opts:tt, impl $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL TRAIT, $opts, [], [], $($rest)+);
+    };
+    // item-unsafe-impl: an unsafe impl is marked by an unsafe at the end of the options, which
+    //    impl-opt-unsafe turns back into the keyword
+    (INTERNAL: ITEM, [$($opts:tt)*], unsafe impl $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ITEM, [$($opts)* unsafe,], impl $($rest)+);
+    };
+    // item-error: anything else is not something guilty! knows how to define
+    (INTERNAL: ITEM, $opts:tt, $($item:tt)*) => {
+        compile_error!(concat!("guilty!: expected a trait or an impl, found `", stringify!($($item)*), "`"));
+    };
+    (INTERNAL: ITEM ATTRS, $opts:tt, $attrs:tt, $($item:tt)*) => {
+        compile_error!(concat!("guilty!: expected a trait or an impl, found `", stringify!($($item)*), "`"));
+    };
+    // impl-generics: collect the generics, keeping a @ in the second square brackets for each
+    //    unclosed nested angle bracket
+    (INTERNAL: IMPL GENERICS, $opts:tt, $generics:tt, [], > $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL TRAIT, $opts, $generics, [], $($rest)+);
+    };
+    (INTERNAL: IMPL GENERICS, $opts:tt, [$($generics:tt)*], [@], >> $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL TRAIT, $opts, [$($generics)* >], [], $($rest)+);
+    };
+    (INTERNAL: IMPL GENERICS, $opts:tt, [$($generics:tt)*], [@ @ $($depth:tt)*], >> $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL GENERICS, $opts, [$($generics)* >>], [$($depth)*], $($rest)+);
+    };
+    (INTERNAL: IMPL GENERICS, $opts:tt, [$($generics:tt)*], [@ $($depth:tt)*], > $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL GENERICS, $opts, [$($generics)* >], [$($depth)*], $($rest)+);
+    };
+    (INTERNAL: IMPL GENERICS, $opts:tt, [$($generics:tt)*], [$($depth:tt)*], < $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL GENERICS, $opts, [$($generics)* <], [@ $($depth)*], $($rest)+);
+    };
+    (INTERNAL: IMPL GENERICS, $opts:tt, [$($generics:tt)*], $depth:tt, $next:tt $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL GENERICS, $opts, [$($generics)* $next], $depth, $($rest)+);
+    };
+    // impl-trait: collect the trait, up to the for
+    (INTERNAL: IMPL TRAIT, $opts:tt, $generics:tt, $traitname:tt, for $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL TYPE, $opts, $generics, $traitname, [], [], [], $($rest)+);
+    };
+    // impl-inherent: without a for, what was collected is the type of an inherent impl, and the
+    //    trait is left empty
+    (INTERNAL: IMPL TRAIT, $opts:tt, $generics:tt, $structname:tt, where $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL WHERE, $opts, [$generics [] $structname], [where], $($rest)+);
+    };
+    (INTERNAL: IMPL TRAIT, $opts:tt, $generics:tt, $structname:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS, [IMPL OPTIONS, $opts, [$generics [] $structname []],], $body);
+    };
+    (INTERNAL: IMPL TRAIT, $opts:tt, $generics:tt, $structname:tt, { $($body:tt)* } $next:ident $($rest:tt)*) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [IMPL TRAIT, $opts, $generics, $structname, { $($body)* }], $next $($rest)*);
+    };
+    (INTERNAL: IMPL TRAIT, $opts:tt, $generics:tt, $structname:tt, { $($body:tt)* } # $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [IMPL TRAIT, $opts, $generics, $structname, { $($body)* }], # $($rest)+);
+    };
+    (INTERNAL: IMPL TRAIT, $opts:tt, $generics:tt, [$($traitname:tt)*], $next:tt $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL TRAIT, $opts, $generics, [$($traitname)* $next], $($rest)+);
+    };
+    // impl-type: collect the type, up to the where clause or the body, keeping a @ in the third
+    //    square brackets for each unclosed angle bracket (as in impl-generics), so that a comma
+    //    outside of them can end the type (see impl-batch); the types before it are kept in the
+    //    first square brackets
+    (INTERNAL: IMPL TYPE, $opts:tt, $generics:tt, $traitname:tt, [], $structname:tt, [], where $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL WHERE, $opts, [$generics $traitname $structname], [where], $($rest)+);
+    };
+    (INTERNAL: IMPL TYPE, $opts:tt, $generics:tt, $traitname:tt, [], $structname:tt, [], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS, [IMPL OPTIONS, $opts, [$generics $traitname $structname []],], $body);
+    };
+    (INTERNAL: IMPL TYPE, $opts:tt, $generics:tt, $traitname:tt, [$($types:tt)+], $structname:tt, [], where $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BATCH, $opts, $generics $traitname, [$($types)+ $structname], [where $($rest)+]);
+    };
+    (INTERNAL: IMPL TYPE, $opts:tt, $generics:tt, $traitname:tt, [$($types:tt)+], $structname:tt, [], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BATCH, $opts, $generics $traitname, [$($types)+ $structname], [$body]);
+    };
+    (INTERNAL: IMPL TYPE, $opts:tt, $generics:tt, $traitname:tt, $types:tt, $structname:tt, [], { $($body:tt)* } $next:ident $($rest:tt)*) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [IMPL TYPE, $opts, $generics, $traitname, $types, $structname, [], { $($body)* }],
+                        $next $($rest)*);
+    };
+    (INTERNAL: IMPL TYPE, $opts:tt, $generics:tt, $traitname:tt, $types:tt, $structname:tt, [], { $($body:tt)* } # $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [IMPL TYPE, $opts, $generics, $traitname, $types, $structname, [], { $($body)* }],
+                        # $($rest)+);
+    };
+    (INTERNAL: IMPL TYPE, $opts:tt, $generics:tt, $traitname:tt, [$($types:tt)*], $structname:tt, [], , $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL TYPE, $opts, $generics, $traitname, [$($types)* $structname], [], [], $($rest)+);
+    };
+    (INTERNAL: IMPL TYPE, $opts:tt, $generics:tt, $traitname:tt, $types:tt, [$($structname:tt)*], [$($depth:tt)*], < $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL TYPE, $opts, $generics, $traitname, $types, [$($structname)* <], [@ $($depth)*], $($rest)+);
+    };
+    (INTERNAL: IMPL TYPE, $opts:tt, $generics:tt, $traitname:tt, $types:tt, [$($structname:tt)*], [@ $($depth:tt)*], > $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL TYPE, $opts, $generics, $traitname, $types, [$($structname)* >], [$($depth)*], $($rest)+);
+    };
+    (INTERNAL: IMPL TYPE, $opts:tt, $generics:tt, $traitname:tt, $types:tt, [$($structname:tt)*], [@ @ $($depth:tt)*], >> $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL TYPE, $opts, $generics, $traitname, $types, [$($structname)* >>], [$($depth)*], $($rest)+);
+    };
+    (INTERNAL: IMPL TYPE, $opts:tt, $generics:tt, $traitname:tt, $types:tt, [$($structname:tt)*], $depth:tt, $next:tt $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL TYPE, $opts, $generics, $traitname, $types, [$($structname)* $next], $depth, $($rest)+);
+    };
+    // impl-batch: an impl for several types, as in impl Trait for A, B, C, is parsed again (from
+    //    the where clause or the body) for each of the types, with the same options
+    (INTERNAL: IMPL BATCH, $opts:tt, $generics:tt $traitname:tt, [$($structname:tt)+], $rest:tt) => {
+        $($crate::__guilty_rules!(INTERNAL: IMPL BATCH, $opts, [$generics $traitname $structname], $rest);)+
+    };
+    (INTERNAL: IMPL BATCH, $opts:tt, $parts:tt, [where $($rest:tt)+]) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL WHERE, $opts, $parts, [where], $($rest)+);
+    };
+    (INTERNAL: IMPL BATCH, $opts:tt, [$($parts:tt)*], [$body:tt]) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS, [IMPL OPTIONS, $opts, [$($parts)* []],], $body);
+    };
+    // impl-where: collect the where clause, up to the body, then go on to the options (via
+    //    separators)
+    (INTERNAL: IMPL WHERE, $opts:tt, [$($parts:tt)*], $where:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS, [IMPL OPTIONS, $opts, [$($parts)* $where],], $body);
+    };
+    (INTERNAL: IMPL WHERE, $opts:tt, $parts:tt, $where:tt, { $($body:tt)* } $next:ident $($rest:tt)*) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [IMPL WHERE, $opts, $parts, $where, { $($body)* }], $next $($rest)*);
+    };
+    (INTERNAL: IMPL WHERE, $opts:tt, $parts:tt, $where:tt, { $($body:tt)* } # $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [IMPL WHERE, $opts, $parts, $where, { $($body)* }], # $($rest)+);
+    };
+    (INTERNAL: IMPL WHERE, $opts:tt, $parts:tt, [$($where:tt)*], $next:tt $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL WHERE, $opts, $parts, [$($where)* $next], $($rest)+);
+    };
+
+    // separators: let the consts of a trait or impl end with a comma instead of a semicolon, and
+    //    drop any stray separators between the items, then pass the body on to the internal call
+    //    given in the first square brackets
+    // a body of nothing but consts ending with semicolons is passed on as it is (after matching a
+    //    copy of it), otherwise the items are moved into the second square brackets one at a time
+    //    (as in def-trait), with the value of each const collected one token tree at a time when
+    //    it is more than a single token tree or a call (see const-value)
+    (INTERNAL: SEPARATORS, $callback:tt, { $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS CLEAN, $callback, { $($body)* }, { $($body)* });
+    };
+    (INTERNAL: SEPARATORS, $callback:tt, $($rest:tt)*) => {
+        compile_error!(concat!("guilty!: expected the body of the trait or impl in braces, found `",
+                               stringify!($($rest)*), "`"));
+    };
+    (INTERNAL: SEPARATORS CLEAN, [$($callback:tt)*], $body:tt,
+     {
+         $(
+             $(#[$($cattr:tt)*])* $(pub $(($($restr:tt)*))*)* const $constname:ident : $consttype:ty
+             $(= $constvalue:expr)*;
+         )*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* $body);
+    };
+    (INTERNAL: SEPARATORS CLEAN, $callback:tt, $body:tt, $copy:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback, [], $body);
+    };
+    (INTERNAL: SEPARATORS BODY, [$($callback:tt)*], [$($done:tt)*], {}) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* { $($done)* });
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, $done:tt, { , $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback, $done, { $($body)* });
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, $done:tt, { ; $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback, $done, { $($body)* });
+    };
+    // separators-fast: a const with a type which is a single token tree (a name, an array...),
+    //    and without a value, or with a value which is a single token tree (a literal, a name, a
+    //    block...) or a call
+    (INTERNAL: SEPARATORS BODY, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($cattr:tt)*])* $(pub $(($($restr:tt)*))*)* const $constname:ident : $consttype:tt ;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback,
+                        [$($done)* $(#[$($cattr)*])* $(pub $(($($restr)*))*)* const $constname : $consttype;],
+                        { $($body)* });
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($cattr:tt)*])* $(pub $(($($restr:tt)*))*)* const $constname:ident : $consttype:tt ,
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback,
+                        [$($done)* $(#[$($cattr)*])* $(pub $(($($restr)*))*)* const $constname : $consttype;],
+                        { $($body)* });
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($cattr:tt)*])* $(pub $(($($restr:tt)*))*)* const $constname:ident : $consttype:tt
+         = $constvalue:tt ;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback,
+                        [$($done)* $(#[$($cattr)*])* $(pub $(($($restr)*))*)* const $constname : $consttype
+                         = $constvalue;],
+                        { $($body)* });
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($cattr:tt)*])* $(pub $(($($restr:tt)*))*)* const $constname:ident : $consttype:tt
+         = $constvalue:tt ,
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback,
+                        [$($done)* $(#[$($cattr)*])* $(pub $(($($restr)*))*)* const $constname : $consttype
+                         = $constvalue;],
+                        { $($body)* });
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($cattr:tt)*])* $(pub $(($($restr:tt)*))*)* const $constname:ident : $consttype:tt
+         = $func:ident $args:tt ;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback,
+                        [$($done)* $(#[$($cattr)*])* $(pub $(($($restr)*))*)* const $constname : $consttype
+                         = $func $args;],
+                        { $($body)* });
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($cattr:tt)*])* $(pub $(($($restr:tt)*))*)* const $constname:ident : $consttype:tt
+         = $func:ident $args:tt ,
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback,
+                        [$($done)* $(#[$($cattr)*])* $(pub $(($($restr)*))*)* const $constname : $consttype
+                         = $func $args;],
+                        { $($body)* });
+    };
+    // separators-const: a const with a type which is a single token tree and any other value goes
+    //    through const-value, and one with any other type through const-type
+    (INTERNAL: SEPARATORS BODY, $callback:tt, $done:tt,
+     {
+         $(#[$($cattr:tt)*])* $(pub $(($($restr:tt)*))*)* const $constname:ident : $consttype:tt = $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST VALUE,
+                        [$callback $done [$(#[$($cattr)*])* $(pub $(($($restr)*))*)* const $constname : $consttype]],
+                        [], [], { $($body)* });
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, $done:tt,
+     {
+         $(#[$($cattr:tt)*])* $(pub $(($($restr:tt)*))*)* const $constname:ident : $first:tt $($body:tt)+
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST TYPE,
+                        [$callback $done [$(#[$($cattr)*])* $(pub $(($($restr)*))*)* const $constname :]],
+                        [$first], [], { $($body)+ });
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, $done:tt,
+     {
+         $(#[$($cattr:tt)*])* $(pub $(($($restr:tt)*))*)* const $constname:ident : $($body:tt)+
+     }) => {
+        compile_error!(concat!("guilty!: expected `;` or `,` after `", stringify!(const $constname : $($body)+), "`"));
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, $done:tt,
+     {
+         $(#[$($cattr:tt)*])* $(pub $(($($restr:tt)*))*)* const $constname:ident $($body:tt)*
+     }) => {
+        compile_error!(concat!("guilty!: const `", stringify!($constname), "` needs a type annotation"));
+    };
+    // separators-fn: fast path for a simple method or associated type, which is moved as it is
+    //    (anything fancier goes through separators-item)
+    (INTERNAL: SEPARATORS BODY, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($fattr:tt)*])* fn $fname:ident $args:tt $(-> $ret:ty)* { $($fbody:tt)* }
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback,
+                        [$($done)* $(#[$($fattr)*])* fn $fname $args $(-> $ret)* { $($fbody)* }], { $($body)* });
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($fattr:tt)*])* fn $fname:ident $args:tt $(-> $ret:ty)* ;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback,
+                        [$($done)* $(#[$($fattr)*])* fn $fname $args $(-> $ret)*;], { $($body)* });
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($tattr:tt)*])* type $tyname:ident $(= $ty:ty)* ;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback,
+                        [$($done)* $(#[$($tattr)*])* type $tyname $(= $ty)*;], { $($body)* });
+    };
+    // separators-item: move any other item, see next-item
+    (INTERNAL: SEPARATORS BODY, $callback:tt, $done:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [SEPARATORS BODY, $callback, $done,], [], $body);
+    };
+    (INTERNAL: SEPARATORS BODY, $callback:tt, [$($done:tt)*], [$($item:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback, [$($done)* $($item)*], $body);
+    };
+    // const-type: collect the type of a const, one token tree at a time, up to the value or the
+    //    separator, so that it is passed on as written (a type matched as a fragment could no
+    //    longer be taken apart by the later steps, as in parse-impl-cached-const)
+    // as in const-value, a @ is kept in the second square brackets for each unclosed angle bracket
+    (INTERNAL: CONST TYPE, [$callback:tt [$($done:tt)*] [$($head:tt)*]], [$($consttype:tt)*], [], { ; $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback, [$($done)* $($head)* $($consttype)*;], { $($body)* });
+    };
+    (INTERNAL: CONST TYPE, [$callback:tt [$($done:tt)*] [$($head:tt)*]], [$($consttype:tt)*], [], { , $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback, [$($done)* $($head)* $($consttype)*;], { $($body)* });
+    };
+    // the value, which is a single token tree or a call as in separators-fast, or goes through
+    //    const-value
+    (INTERNAL: CONST TYPE, [$callback:tt [$($done:tt)*] [$($head:tt)*]], [$($consttype:tt)*], [],
+     { = $constvalue:tt ; $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback, [$($done)* $($head)* $($consttype)* = $constvalue;], { $($body)* });
+    };
+    (INTERNAL: CONST TYPE, [$callback:tt [$($done:tt)*] [$($head:tt)*]], [$($consttype:tt)*], [],
+     { = $constvalue:tt , $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback, [$($done)* $($head)* $($consttype)* = $constvalue;], { $($body)* });
+    };
+    (INTERNAL: CONST TYPE, [$callback:tt [$($done:tt)*] [$($head:tt)*]], [$($consttype:tt)*], [],
+     { = $func:ident $args:tt ; $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback, [$($done)* $($head)* $($consttype)* = $func $args;], { $($body)* });
+    };
+    (INTERNAL: CONST TYPE, [$callback:tt [$($done:tt)*] [$($head:tt)*]], [$($consttype:tt)*], [],
+     { = $func:ident $args:tt , $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback, [$($done)* $($head)* $($consttype)* = $func $args;], { $($body)* });
+    };
+    (INTERNAL: CONST TYPE, [$callback:tt $done:tt [$($head:tt)*]], [$($consttype:tt)*], [], { = $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST VALUE, [$callback $done [$($head)* $($consttype)*]], [], [], { $($body)* });
+    };
+    // angle brackets, including those closed by the same token as the = (as in `Option<u8>= None`)
+    (INTERNAL: CONST TYPE, $state:tt, [$($consttype:tt)*], [$($depth:tt)*], { < $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST TYPE, $state, [$($consttype)* <], [@ $($depth)*], { $($body)* });
+    };
+    (INTERNAL: CONST TYPE, $state:tt, [$($consttype:tt)*], [$($depth:tt)*], { << $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST TYPE, $state, [$($consttype)* <<], [@ @ $($depth)*], { $($body)* });
+    };
+    (INTERNAL: CONST TYPE, $state:tt, [$($consttype:tt)*], [@ $($depth:tt)*], { > $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST TYPE, $state, [$($consttype)* >], [$($depth)*], { $($body)* });
+    };
+    (INTERNAL: CONST TYPE, $state:tt, [$($consttype:tt)*], [@ @ $($depth:tt)*], { >> $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST TYPE, $state, [$($consttype)* >>], [$($depth)*], { $($body)* });
+    };
+    (INTERNAL: CONST TYPE, $state:tt, [$($consttype:tt)*], [@], { >= $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST TYPE, $state, [$($consttype)* >], [], { = $($body)* });
+    };
+    (INTERNAL: CONST TYPE, $state:tt, [$($consttype:tt)*], [@ @], { >>= $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST TYPE, $state, [$($consttype)* >>], [], { = $($body)* });
+    };
+    (INTERNAL: CONST TYPE, [$callback:tt $done:tt [$($head:tt)*]], [$($consttype:tt)*], $depth:tt, {}) => {
+        compile_error!(concat!("guilty!: expected `;` or `,` after `", stringify!($($head)* $($consttype)*), "`"));
+    };
+    (INTERNAL: CONST TYPE, $state:tt, [$($consttype:tt)*], $depth:tt, { $next:tt $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST TYPE, $state, [$($consttype)* $next], $depth, { $($body)* });
+    };
+    // const-value: collect the value of a const, up to the separator (reaching the end of the body
+    //    means that the separator is missing)
+    (INTERNAL: CONST VALUE, [$callback:tt $done:tt [$($head:tt)*]], [$($constvalue:tt)*], $depth:tt, {}) => {
+        compile_error!(concat!("guilty!: expected `;` or `,` after `", stringify!($($head)* = $($constvalue)*), "`"));
+    };
+    // a comma can also separate generic arguments, so a @ is kept in the second square brackets for
+    //    each unclosed angle bracket (as in impl-generics), but only after :: or at the start of the
+    //    value (where they can't be comparisons)
+    (INTERNAL: CONST VALUE, [$callback:tt [$($done:tt)*] [$($head:tt)*]], [$($constvalue:tt)*], [], { , $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback, [$($done)* $($head)* = $($constvalue)*;], { $($body)* });
+    };
+    (INTERNAL: CONST VALUE, [$callback:tt [$($done:tt)*] [$($head:tt)*]], [$($constvalue:tt)*], $depth:tt, { ; $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SEPARATORS BODY, $callback, [$($done)* $($head)* = $($constvalue)*;], { $($body)* });
+    };
+    // commas can also separate the parameters of a closure, so those are collected at once (see
+    //    closure-params)
+    (INTERNAL: CONST VALUE, $state:tt, [], [], { | $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CLOSURE PARAMS, $state, [|], { $($body)* });
+    };
+    (INTERNAL: CONST VALUE, $state:tt, [], [], { move | $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CLOSURE PARAMS, $state, [move |], { $($body)* });
+    };
+    (INTERNAL: CONST VALUE, $state:tt, [], [], { < $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST VALUE, $state, [<], [@], { $($body)* });
+    };
+    (INTERNAL: CONST VALUE, $state:tt, [], [], { << $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST VALUE, $state, [<<], [@ @], { $($body)* });
+    };
+    (INTERNAL: CONST VALUE, $state:tt, [$($constvalue:tt)*], [$($depth:tt)*], { :: < $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST VALUE, $state, [$($constvalue)* :: <], [@ $($depth)*], { $($body)* });
+    };
+    (INTERNAL: CONST VALUE, $state:tt, [$($constvalue:tt)*], [@ $($depth:tt)*], { > $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST VALUE, $state, [$($constvalue)* >], [$($depth)*], { $($body)* });
+    };
+    (INTERNAL: CONST VALUE, $state:tt, [$($constvalue:tt)*], [@ @ $($depth:tt)*], { >> $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST VALUE, $state, [$($constvalue)* >>], [$($depth)*], { $($body)* });
+    };
+    (INTERNAL: CONST VALUE, $state:tt, [$($constvalue:tt)*], [@], { >> $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST VALUE, $state, [$($constvalue)* >>], [], { $($body)* });
+    };
+    (INTERNAL: CONST VALUE, $state:tt, [$($constvalue:tt)*], $depth:tt, { $next:tt $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST VALUE, $state, [$($constvalue)* $next], $depth, { $($body)* });
+    };
+
+    // closure-params: collect the parameters of a closure at the start of the value of a const, up
+    //    to the closing |, then go back to const-value for the rest of the value
+    (INTERNAL: CLOSURE PARAMS, $state:tt, [$($constvalue:tt)*], { | $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CONST VALUE, $state, [$($constvalue)* |], [], { $($body)* });
+    };
+    (INTERNAL: CLOSURE PARAMS, $state:tt, [$($constvalue:tt)*], { $next:tt $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: CLOSURE PARAMS, $state, [$($constvalue)* $next], { $($body)* });
+    };
+    (INTERNAL: CLOSURE PARAMS, $state:tt, $constvalue:tt, {}) => {
+        $crate::__guilty_rules!(INTERNAL: CONST VALUE, $state, $constvalue, [], {});
+    };
+
+    // trait-opt-fingerprint: the fingerprint option declares a CONSTS_FINGERPRINT const, to be
+    //    filled in by impls with the same option, and a method hashing the consts of the trait
+    //    with their defaults, which the impls start from (so that an impl which takes a default
+    //    notices when it changes)
+    // calls on to trait-defaults (with a copy of the body) to collect the consts for
+    //    trait-fingerprint-def
+    (INTERNAL: TRAIT OPTIONS, [fingerprint, $($opts:tt)*], $attrs:tt $before:tt $traitname:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT DEFAULTS,
+                [REFLECTED, [CFG ONLY, [TRAIT FINGERPRINT, [$($opts)*] $attrs $before $traitname $body,], [],], [],],
+                [], $body);
+    };
+    // trait-fingerprint-def: add the consts, then continue with the options
+    (INTERNAL: TRAIT FINGERPRINT, $opts:tt $attrs:tt $before:tt $traitname:tt
+     {
+         $($body:tt)*
+     },
+     [$([$(#[$cattr:meta])*] $constname:ident : $consttype:ty $(= $constdefault:expr)*;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, $opts, $attrs $before $traitname,
+                {
+                    /// Hash of the names, types and values of the consts in the impl
+                    const CONSTS_FINGERPRINT: u64;
+                    #[doc(hidden)]
+                    fn __guilty_trait_fingerprint() -> u64 where Self: Sized {
+                        #[allow(unused_mut)]
+                        let mut hash = $crate::__fingerprint("");
+                        $($(#[$cattr])* {
+                            hash = $crate::__fingerprint_extend(hash, concat!(
+                                stringify!($constname), ":", stringify!($consttype), $("=", stringify!($constdefault),)* ";"));
+                        })*
+                        hash
+                    }
+                    $($body)*
+                });
+    };
+    // trait-opt-inherit: the inherit(...) option declares the listed consts of a supertrait in the
+    //    trait as well, with defaults forwarding to the supertrait
+    (INTERNAL: TRAIT OPTIONS, [inherit($supertrait:path { $($(#[$cattr:meta])* const $constname:ident : $consttype:ty;)* }), $($opts:tt)*],
+     $attrs:tt $before:tt $traitname:tt,
+     {
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, [$($opts)*], $attrs $before $traitname,
+                {
+                    $(
+                        $(#[$cattr])*
+                        #[doc = concat!("Same as `", stringify!($supertrait), "::", stringify!($constname), "`")]
+                        const $constname: $consttype
+                            = $crate::__guilty_mangle!([__guilty_mangle unmangled] <Self as $supertrait>::__guilty_mangle $constname());
+                    )*
+                    $($body)*
+                });
+    };
+    // trait-opt-erased: the erased option generates an object-safe companion trait with the given
+    //    name, whose methods take &self and return the const values, and a blanket impl of it for
+    //    all implementors
+    // calls on to trait-consts (with a copy of the body) to collect the consts for erased-def
+    (INTERNAL: TRAIT OPTIONS, [erased = $erased:ident, $($opts:tt)*],
+     [$(#[$attr:meta])*] [$($before:tt)+] [$traitname:ident $($parent:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT CONSTS,
+                [REFLECTED, [ERASED, [$($opts)* $(#[$attr])*] [$($before)+] $traitname $erased,], [],], [], $body);
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, [$($opts)*], [$(#[$attr])*] [$($before)+] [$traitname $($parent)*], $body);
+    };
+    // erased-def: output the companion trait, then the blanket impl (with only the cfg attributes
+    //    of the consts)
+    (INTERNAL: ERASED, $mode:tt [$($before:tt)+] $traitname:ident $erased:ident,
+     [$([$(#[$($cattr:tt)*])*] $constname:ident : $consttype:ty;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: AS ITEM, $mode
+                #[doc = concat!("Object-safe mirror of the consts of `", stringify!($traitname), "`")]
+                $($before)+ $erased {
+                    $($(#[$($cattr)*])* #[allow(non_snake_case)] fn __guilty_mangle $constname(&self) -> $consttype;)*
+                });
+        $crate::__guilty_rules!(INTERNAL: CFG ONLY, [ERASED IMPL, $mode $traitname $erased,], [],
+                        [$([$(#[$($cattr)*])*] $constname : $consttype;)*]);
+    };
+    (INTERNAL: ERASED IMPL, $mode:tt $traitname:ident $erased:ident,
+     [$([$(#[$cattr:meta])*] $constname:ident : $consttype:ty;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: AS ITEM, $mode
+                impl<__T: $traitname> $erased for __T {
+                    $($(#[$cattr])* #[allow(non_snake_case)] fn __guilty_mangle $constname(&self) -> $consttype {
+                        <__T as $traitname>::__guilty_mangle $constname()
+                    })*
+                });
+    };
+    // trait-opt-aggregates: the aggregates(...) option adds consts with defaults computed from all
+    //    the other consts, which must share an ordered type: any of CONSTS_MAX, CONSTS_MIN and
+    //    CONSTS_SUM (the latter also requires Add)
+    // calls on to trait-consts (with a copy of the body) to collect the consts for aggregates-def
+    (INTERNAL: TRAIT OPTIONS, [aggregates($($agg:ident),*), $($opts:tt)*],
+     [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT CONSTS,
+                [CFG ONLY, [AGGREGATES, [$($agg)*] [$($opts)*] [$(#[$attr])*] [$($before)+] [$($traitname)*] $body,], [],],
+                [], $body);
+    };
+    // aggregates-def: add the aggregates one at a time, then continue with the options
+    // the consts may be cfg-gated, so they are folded into an Option, which is None if they are all
+    //    gated out: aggregates-none makes that an error, and init is the function of the last const
+    //    which is compiled in (as there is no value to fall back on otherwise)
+    (INTERNAL: AGGREGATES, [max $($agg:ident)*] $opts:tt $attrs:tt $before:tt $traitname:tt
+     {
+         $($body:tt)*
+     },
+     [[$(#[cfg $firstpred:tt])*] $first:ident : $consttype:ty; $([$(#[cfg $pred:tt])*] $constname:ident : $othertype:ty;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: AGGREGATES, [$($agg)*] $opts $attrs $before $traitname
+                {
+                    /// The maximum of the other consts
+                    const CONSTS_MAX: $consttype = $crate::__guilty_mangle!([__guilty_mangle unmangled] {
+                        $(#[cfg $firstpred])* #[allow(unused_variables)] let init: fn() -> $consttype = Self::__guilty_mangle $first;
+                        $($(#[cfg $pred])* #[allow(unused_variables)] let init: fn() -> $consttype = Self::__guilty_mangle $constname;)*
+                        let max: $crate::__core::option::Option<$consttype> = $crate::__core::option::Option::None;
+                        $(#[cfg $firstpred])* let max = $crate::__aggregate(max, Self::__guilty_mangle $first(), $crate::__max);
+                        $($(#[cfg $pred])* let max = $crate::__aggregate(max, Self::__guilty_mangle $constname(), $crate::__max);)*
+                        max.unwrap_or_else(init)
+                    });
+                    $($body)*
+                },
+                [[$(#[cfg $firstpred])*] $first : $consttype; $([$(#[cfg $pred])*] $constname : $othertype;)*]);
+    };
+    (INTERNAL: AGGREGATES, [min $($agg:ident)*] $opts:tt $attrs:tt $before:tt $traitname:tt
+     {
+         $($body:tt)*
+     },
+     [[$(#[cfg $firstpred:tt])*] $first:ident : $consttype:ty; $([$(#[cfg $pred:tt])*] $constname:ident : $othertype:ty;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: AGGREGATES, [$($agg)*] $opts $attrs $before $traitname
+                {
+                    /// The minimum of the other consts
+                    const CONSTS_MIN: $consttype = $crate::__guilty_mangle!([__guilty_mangle unmangled] {
+                        $(#[cfg $firstpred])* #[allow(unused_variables)] let init: fn() -> $consttype = Self::__guilty_mangle $first;
+                        $($(#[cfg $pred])* #[allow(unused_variables)] let init: fn() -> $consttype = Self::__guilty_mangle $constname;)*
+                        let min: $crate::__core::option::Option<$consttype> = $crate::__core::option::Option::None;
+                        $(#[cfg $firstpred])* let min = $crate::__aggregate(min, Self::__guilty_mangle $first(), $crate::__min);
+                        $($(#[cfg $pred])* let min = $crate::__aggregate(min, Self::__guilty_mangle $constname(), $crate::__min);)*
+                        min.unwrap_or_else(init)
+                    });
+                    $($body)*
+                },
+                [[$(#[cfg $firstpred])*] $first : $consttype; $([$(#[cfg $pred])*] $constname : $othertype;)*]);
+    };
+    (INTERNAL: AGGREGATES, [sum $($agg:ident)*] $opts:tt $attrs:tt $before:tt $traitname:tt
+     {
+         $($body:tt)*
+     },
+     [[$(#[cfg $firstpred:tt])*] $first:ident : $consttype:ty; $([$(#[cfg $pred:tt])*] $constname:ident : $othertype:ty;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: AGGREGATES, [$($agg)*] $opts $attrs $before $traitname
+                {
+                    /// The sum of the other consts
+                    const CONSTS_SUM: $consttype = $crate::__guilty_mangle!([__guilty_mangle unmangled] {
+                        $(#[cfg $firstpred])* #[allow(unused_variables)] let init: fn() -> $consttype = Self::__guilty_mangle $first;
+                        $($(#[cfg $pred])* #[allow(unused_variables)] let init: fn() -> $consttype = Self::__guilty_mangle $constname;)*
+                        let sum: $crate::__core::option::Option<$consttype> = $crate::__core::option::Option::None;
+                        $(#[cfg $firstpred])* let sum = $crate::__aggregate(sum, Self::__guilty_mangle $first(), $crate::__core::ops::Add::add);
+                        $($(#[cfg $pred])* let sum = $crate::__aggregate(sum, Self::__guilty_mangle $constname(), $crate::__core::ops::Add::add);)*
+                        sum.unwrap_or_else(init)
+                    });
+                    $($body)*
+                },
+                [[$(#[cfg $firstpred])*] $first : $consttype; $([$(#[cfg $pred])*] $constname : $othertype;)*]);
+    };
+    // aggregates-none: a trait without consts to aggregate is an error, as is one whose consts are
+    //    all cfg-gated out (which is checked with a cfg of its own, since the macro can't tell)
+    (INTERNAL: AGGREGATES, [$($agg:ident)+] $opts:tt $attrs:tt $before:tt $traitname:tt $body:tt, []) => {
+        compile_error!("guilty!: aggregates need at least one const");
+    };
+    (INTERNAL: AGGREGATES, [] $opts:tt $attrs:tt $before:tt $traitname:tt $body:tt,
+     [$([$(#[cfg $pred:tt])*] $constname:ident : $consttype:ty;)*]) => {
+        #[cfg(not(any($(all($(any $pred),*)),*)))]
+        compile_error!("guilty!: aggregates need at least one const");
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, $opts, $attrs $before $traitname, $body);
+    };
+    (INTERNAL: AGGREGATES, [$agg:ident $($aggs:ident)*] $($rest:tt)*) => {
+        compile_error!(concat!("guilty!: unknown aggregate `", stringify!($agg), "` (expected max, min or sum)"));
+    };
+    // trait-opt-name-of: the name_of option adds a method for finding the name of the const with a
+    //    given value, when all the consts share a type that implements PartialEq
+    // calls on to trait-consts (with a copy of the body) to collect the consts for name-of-def
+    (INTERNAL: TRAIT OPTIONS, [name_of, $($opts:tt)*],
+     [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT CONSTS,
+                [REFLECTED, [CFG ONLY, [NAME OF, [$($opts)*] [$(#[$attr])*] [$($before)+] [$($traitname)*] $body,], [],], [],],
+                [], $body);
+    };
+    // name-of-def: append the method to the trait, then continue with the options
+    (INTERNAL: NAME OF, $opts:tt $attrs:tt $before:tt $traitname:tt
+     {
+         $($body:tt)*
+     },
+     [[$(#[$firstattr:meta])*] $first:ident : $consttype:ty; $([$(#[$cattr:meta])*] $constname:ident : $othertype:ty;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, $opts, $attrs $before $traitname,
+                {
+                    $($body)*
+                    /// Returns the name of the (first) const that is equal to `value`, if any
+                    fn name_of(value: &$consttype) -> $crate::__core::option::Option<&'static str> {
+                        $(#[$firstattr])*
+                        if *value == Self::__guilty_mangle $first() {
+                            return $crate::__core::option::Option::Some(stringify!($first));
+                        }
+                        $($(#[$cattr])* if *value == Self::__guilty_mangle $constname() {
+                            return $crate::__core::option::Option::Some(stringify!($constname));
+                        })*
+                        $crate::__core::option::Option::None
+                    }
+                });
+    };
+    // trait-opt-introspect: the introspect option adds methods listing the consts of the trait,
+    //    and visiting their names, types and values in each impl
+    // calls on to trait-consts (with a copy of the body) to collect the consts for introspect-def
+    (INTERNAL: TRAIT OPTIONS, [introspect, $($opts:tt)*],
+     [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT CONSTS,
+                [REFLECTED, [CFG ONLY, [INTROSPECT, [$($opts)*] [$(#[$attr])*] [$($before)+] [$($traitname)*] $body,], [],], [],],
+                [], $body);
+    };
+    // introspect-def: append the methods to the trait, then continue with the options
+    (INTERNAL: INTROSPECT, $opts:tt $attrs:tt $before:tt $traitname:tt
+     {
+         $($body:tt)*
+     },
+     [$([$(#[$cattr:meta])*] $constname:ident : $consttype:ty;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, $opts, $attrs $before $traitname,
+                {
+                    $($body)*
+                    /// Returns the names of the consts declared by the trait, in order
+                    fn __guilty_const_names() -> &'static [&'static str] where Self: Sized {
+                        &[$($(#[$cattr])* stringify!($constname)),*]
+                    }
+                    /// Calls `visit` with the name, type (as written) and value of each const, in order
+                    fn __guilty_describe(visit: &mut dyn FnMut(&'static str, &'static str, &dyn $crate::__core::fmt::Debug))
+                        where Self: Sized
+                    {
+                        $($(#[$cattr])* visit(stringify!($constname), stringify!($consttype), &Self::__guilty_mangle $constname());)*
+                    }
+                });
+    };
+    // trait-opt-provenance: the provenance option adds methods returning where the consts are
+    //    defined (see locate-const), which impls with the same option override
+    // calls on to trait-consts (with a copy of the body) to collect the consts for trait-provenance-def
+    (INTERNAL: TRAIT OPTIONS, [provenance, $($opts:tt)*],
+     [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT CONSTS,
+                [REFLECTED, [TRAIT PROVENANCE, [$($opts)*] [$(#[$attr])*] [$($before)+] [$($traitname)*] $body,], [],],
+                [], $body);
+    };
+    // trait-provenance-def: append the methods to the trait, then continue with the options
+    (INTERNAL: TRAIT PROVENANCE, $opts:tt $attrs:tt $before:tt $traitname:tt
+     {
+         $($body:tt)*
+     },
+     [$([$(#[$cattr:meta])*] $constname:ident : $consttype:ty;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, $opts, $attrs $before $traitname,
+                {
+                    $($body)*
+                    #[doc(hidden)]
+                    fn __guilty_trait_location(name: &str) -> $crate::__core::option::Option<(&'static str, u32)> {
+                        if [$(stringify!($constname)),*].contains(&name) {
+                            $crate::__guilty_location!()
+                        } else {
+                            $crate::__core::option::Option::None
+                        }
+                    }
+                    #[doc(hidden)]
+                    fn __guilty_location(name: &str) -> $crate::__core::option::Option<(&'static str, u32)> {
+                        Self::__guilty_trait_location(name)
+                    }
+                });
+    };
+    // trait-opt-blanket: the blanket option implements the trait for every type implementing its
+    //    supertraits, so that the consts all take their defaults
+    (INTERNAL: TRAIT OPTIONS, [blanket, $($opts:tt)*], $attrs:tt $before:tt [$traitname:ident : $($supers:tt)+], $body:tt) => {
+        impl<__T: ?Sized + $($supers)+> $traitname for __T {}
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, [$($opts)*], $attrs $before [$traitname : $($supers)+], $body);
+    };
+    (INTERNAL: TRAIT OPTIONS, [blanket, $($opts:tt)*], $attrs:tt $before:tt [$($traitname:tt)*], $body:tt) => {
+        compile_error!(concat!("guilty!: the blanket option needs trait `", stringify!($($traitname)*),
+                               "` to have supertraits, and no lifetime parameters"));
+    };
+    // trait-opt-overrides: the track_overrides option declares a method listing the consts which
+    //    an impl defines itself, to be filled in by impls with the same option
+    (INTERNAL: TRAIT OPTIONS, [track_overrides, $($opts:tt)*], [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*],
+     {
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, [$($opts)*], [$(#[$attr])*] [$($before)+] [$($traitname)*],
+                {
+                    $($body)*
+                    #[doc(hidden)]
+                    fn __guilty_overridden() -> &'static [&'static str];
+                });
+    };
+    // trait-opt-invariant: the invariant(...) option adds a method asserting that the given
+    //    condition (which can access consts as __guilty_rules!(Self::CONST)) holds in an impl
+    // calls on to invariants-collect, because all invariants have to end up in one method
+    (INTERNAL: TRAIT OPTIONS, [invariant($($inv:tt)*), $($opts:tt)*],
+     [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: INVARIANTS, [($($inv)*)], [], [$($opts)*],
+                [$(#[$attr])*] [$($before)+] [$($traitname)*], $body);
+    };
+    // invariants-collect: pull any further invariants out of the remaining options, keeping the rest
+    (INTERNAL: INVARIANTS, [$($invs:tt)*], [$($kept:tt)*], [invariant($($inv:tt)*), $($opts:tt)*],
+     $attrs:tt $before:tt $traitname:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: INVARIANTS, [$($invs)* ($($inv)*)], [$($kept)*], [$($opts)*],
+                $attrs $before $traitname, $body);
+    };
+    (INTERNAL: INVARIANTS, [$($invs:tt)*], [$($kept:tt)*], [$opt:tt $($opts:tt)*],
+     $attrs:tt $before:tt $traitname:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: INVARIANTS, [$($invs)*], [$($kept)* $opt], [$($opts)*],
+                $attrs $before $traitname, $body);
+    };
+    // invariants-def: append the checking method, and the hook which the impls with the
+    //    check_invariants option call as their consts are accessed (see impl-opt-check-invariants),
+    //    then continue with the other options (the hook is bounded, to keep the trait object safe)
+    (INTERNAL: INVARIANTS, [$(($($inv:tt)*))*], [$($kept:tt)*], [],
+     $attrs:tt $before:tt $traitname:tt,
+     {
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, [$($kept)*], $attrs $before $traitname,
+                {
+                    $($body)*
+                    /// Panics if the consts of the impl violate one of the trait's invariants
+                    fn assert_invariants() {
+                        $(assert!($($inv)*, "{}: invariant `{}` does not hold",
+                                  $crate::__core::any::type_name::<Self>(), stringify!($($inv)*));)*
+                    }
+                    #[doc(hidden)]
+                    fn __guilty_check_invariants() where Self: Sized {
+                        if cfg!(debug_assertions) {
+                            $crate::__check_invariants(Self::assert_invariants);
+                        }
+                    }
+                });
+    };
+    // trait-opt-self-consts: the self_consts option rewrites Self::CONST in the method bodies into
+    //    accesses of the consts (see self-consts)
+    (INTERNAL: TRAIT OPTIONS, [self_consts, $($opts:tt)*], $attrs:tt $before:tt $traitname:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SELF CONSTS, [TRAIT OPTIONS, [$($opts)*], $attrs $before $traitname,], [], $body);
+    };
+    // trait-opt-object-safe: the object_safe option keeps the trait object safe, by adding a
+    //    `where Self: Sized` bound to the functions generated for the consts
+    // it is applied after all of the other options (moving itself to the end of the list), and
+    //    goes through the items of the trait (collecting them in square brackets, as in
+    //    def-trait), turning the consts into functions itself
+    (INTERNAL: TRAIT OPTIONS, [object_safe, $(,)*], $attrs:tt $before:tt $traitname:tt, { $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: OBJECT SAFE, $attrs $before $traitname, [], { $($body)* });
+    };
+    (INTERNAL: TRAIT OPTIONS, [object_safe, $($opts:tt)+], $attrs:tt $before:tt $traitname:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, [$($opts)+ object_safe,], $attrs $before $traitname, $body);
+    };
+    // object-safe-fn-attrs: same as parse-trait-fn-attrs
+    (INTERNAL: OBJECT SAFE, $attrs:tt $before:tt $traitname:tt, $done:tt,
+     {
+         #[guilty(fn_attr($($fattr:meta),*))] $(#[$($cattr:tt)*])* const $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: OBJECT SAFE, $attrs $before $traitname, $done,
+                { $(#[$($cattr)*])* $(#[$fattr])* const $($body)* });
+    };
+    // object-safe-noreflect: drop the no_reflect marker (the other options have been applied)
+    (INTERNAL: OBJECT SAFE, $attrs:tt $before:tt $traitname:tt, $done:tt,
+     {
+         #[guilty(no_reflect)] $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: OBJECT SAFE, $attrs $before $traitname, $done, { $($body)* });
+    };
+    // object-safe-typenum: same as parse-trait-typenum
+    (INTERNAL: OBJECT SAFE, $attrs:tt $before:tt $traitname:tt, $done:tt,
+     {
+         #[guilty(typenum = $tyname:ident)] $(#[$($cattr:tt)*])* const $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ATTRS,
+                        [TYPENUM TYPE, [OBJECT SAFE, $attrs $before $traitname,], $done,
+                         [#[allow(non_camel_case_types)] type $tyname: ::typenum::Unsigned;], { $(#[$($cattr)*])* const $($body)* },],
+                        [], [$(#[$($cattr)*])*]);
+    };
+    // object-safe-per-variant: move a const with the per_variant option as it is (see
+    //    parse-trait-per-variant), since a method taking &self is object safe already
+    (INTERNAL: OBJECT SAFE, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         #[guilty(per_variant)] $(#[$cattr:meta])* const $constname:ident : $consttype:ty $(= $constdefault:expr)*;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: OBJECT SAFE, $attrs $before $traitname,
+                [$($done)* #[guilty(per_variant)] $(#[$cattr])* const $constname : $consttype $(= $constdefault)*;],
+                { $($body)* });
+    };
+    // object-safe-const: same as parse-trait-litconst, parse-trait-defconst,
+    //    parse-trait-nodefconst and parse-trait-static, with the bound
+    (INTERNAL: OBJECT SAFE, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constdefault:literal;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: OBJECT SAFE, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[doc = ""] #[doc = concat!("Default: `", stringify!($constdefault), "`")]
+                    #[allow(non_snake_case)] fn __guilty_mangle $constname() -> $consttype where Self: Sized { $constdefault }
+                ],
+                { $($body)* });
+    };
+    (INTERNAL: OBJECT SAFE, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constdefault:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: OBJECT SAFE, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] fn __guilty_mangle $constname() -> $consttype where Self: Sized { $constdefault }
+                ],
+                { $($body)* });
+    };
+    (INTERNAL: OBJECT SAFE, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* const $constname:ident : $consttype:ty;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: OBJECT SAFE, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] fn __guilty_mangle $constname() -> $consttype where Self: Sized;
+                ],
+                { $($body)* });
+    };
+    (INTERNAL: OBJECT SAFE, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* static $staticname:ident : $statictype:ty;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: OBJECT SAFE, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] fn __guilty_mangle $staticname() -> &'static $statictype where Self: Sized;
+                ],
+                { $($body)* });
+    };
+    // object-safe-invariants: the method added by invariants-def gets the bound as well
+    (INTERNAL: OBJECT SAFE, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$fattr:meta])* fn assert_invariants() { $($fbody:tt)* }
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: OBJECT SAFE, $attrs $before $traitname,
+                [$($done)* $(#[$fattr])* fn assert_invariants() where Self: Sized { $($fbody)* }],
+                { $($body)* });
+    };
+    // object-safe-done: all items are seen, go on to parse the trait
+    (INTERNAL: OBJECT SAFE, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*], {}) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, [], $attrs $before $traitname, { $($done)* });
+    };
+    // object-safe-item: move any other item as it is, see next-item
+    (INTERNAL: OBJECT SAFE, $attrs:tt $before:tt $traitname:tt, $done:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [OBJECT SAFE, $attrs $before $traitname, $done,], [], $body);
+    };
+    (INTERNAL: OBJECT SAFE, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*], [$($item:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: OBJECT SAFE, $attrs $before $traitname, [$($done)* $($item)*], $body);
+    };
+    // trait-opt-consts-mod: the consts_mod option moves the consts out of the trait, into a trait
+    //    of the same name in a hidden module with the given name, which becomes a supertrait
+    // calls on to split-trait, which separates the consts from the rest of the body (collecting
+    //    each in its own square brackets, as in def-trait)
+    (INTERNAL: TRAIT OPTIONS, [consts_mod = $m:ident, $($opts:tt)*],
+     [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*], { $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SPLIT TRAIT, [$m [$($opts)*] [$(#[$attr])*] [$($before)+] [$($traitname)*]], [], [],
+                        { $($body)* });
+    };
+    // split-trait: move a const from the body to the consts
+    (INTERNAL: SPLIT TRAIT, $state:tt, [$($consts:tt)*], $others:tt,
+     {
+         $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty $(= $constdefault:expr)*;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SPLIT TRAIT, $state,
+                        [$($consts)* $(#[$($cattr)*])* const $constname : $consttype $(= $constdefault)*;],
+                        $others, { $($body)* });
+    };
+    // split-trait-type: move an associated type to the consts as well, since their types may
+    //    mention it (as in `Self::Output`), see next-item
+    (INTERNAL: SPLIT TRAIT, $state:tt, $consts:tt, $others:tt,
+     {
+         $(#[$($tattr:tt)*])* type $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [SPLIT TRAIT TYPE, $state, $consts, $others,], [],
+                        { $(#[$($tattr)*])* type $($body)* });
+    };
+    (INTERNAL: SPLIT TRAIT TYPE, $state:tt, [$($consts:tt)*], $others:tt, [$($item:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SPLIT TRAIT, $state, [$($consts)* $($item)*], $others, $body);
+    };
+    // split-trait-item: move any other item to the other items, see next-item
+    (INTERNAL: SPLIT TRAIT, $state:tt, $consts:tt, [$($others:tt)*], {}) => {
+        $crate::__guilty_rules!(INTERNAL: SPLIT TRAIT DONE, $state, $consts, { $($others)* });
+    };
+    (INTERNAL: SPLIT TRAIT, $state:tt, $consts:tt, $others:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [SPLIT TRAIT, $state, $consts, $others,], [], $body);
+    };
+    (INTERNAL: SPLIT TRAIT, $state:tt, $consts:tt, [$($others:tt)*], [$($item:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SPLIT TRAIT, $state, $consts, [$($others)* $($item)*], $body);
+    };
+    // split-trait-done: output the module, then continue with the options
+    (INTERNAL: SPLIT TRAIT DONE, [$m:ident $opts:tt $attrs:tt $before:tt [$traitname:ident $($parent:tt)*]],
+     [$($consts:tt)*], $body:tt) => {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        pub mod $m {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $crate::__guilty_rules!(INTERNAL: DEFINE TRAIT, [#[__guilty_mangle [$opts $attrs]]] [pub trait] [$traitname],
+                            { $($consts)* });
+        }
+        $crate::__guilty_rules!(INTERNAL: SUPERTRAIT, $m $opts $attrs $before [$traitname $($parent)*], $body);
+    };
+    // supertrait: add the trait in the consts module as a supertrait
+    (INTERNAL: SUPERTRAIT, $m:ident $opts:tt $attrs:tt $before:tt [$traitname:ident], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, $opts, $attrs $before [$traitname : $m::$traitname], $body);
+    };
+    (INTERNAL: SUPERTRAIT, $m:ident $opts:tt $attrs:tt $before:tt [$traitname:ident : $($bounds:tt)+], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, $opts, $attrs $before [$traitname : $m::$traitname + $($bounds)+], $body);
+    };
+    (INTERNAL: SUPERTRAIT, $m:ident $opts:tt $attrs:tt $before:tt [$traitname:ident where $($bounds:tt)+], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, $opts, $attrs $before [$traitname : $m::$traitname where $($bounds)+],
+                        $body);
+    };
+    // trait-opt-comma: skip a stray separator between options
+    (INTERNAL: TRAIT OPTIONS, [, $($opts:tt)*], [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, [$($opts)*], [$(#[$attr])*] [$($before)+] [$($traitname)*], $body);
+    };
+    // trait-opt-done: all options are processed, go on to parse the trait
+    (INTERNAL: TRAIT OPTIONS, [], [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: DEFINE TRAIT, [$(#[$attr])*] [$($before)+] [$($traitname)*], $body);
+    };
+    // trait-opt-unmangled: the unmangled option keeps the names of the functions the same as
+    //    the consts (as in older versions), which is done by item-redir when it sees the attribute
+    (INTERNAL: TRAIT OPTIONS, [unmangled, $($opts:tt)*],
+     [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT OPTIONS, [$($opts)*],
+                [$(#[$attr])* #[__guilty_mangle(unmangled)]] [$($before)+] [$($traitname)*], $body);
+    };
+    // trait-opt-error: an option which is not one of the above
+    (INTERNAL: TRAIT OPTIONS, [$opt:tt $($opts:tt)*], $($rest:tt)*) => {
+        compile_error!(concat!("guilty!: unknown option `", stringify!($opt), "` for a trait (or wrong syntax for it)"));
+    };
+
+    // trait-consts: collect the consts of a trait, without outputting anything
+    // at the end of the body, the list is passed on to the internal call given in the first
+    //    square brackets, as [[attrs] NAME: Type; ...]
+    // trait-consts-fast: a body of nothing but consts is collected at once
+    (INTERNAL: TRAIT CONSTS, [$($callback:tt)*], [],
+     {
+         $($(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty $(= $constdefault:expr)*;)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* [$([$(#[$($cattr)*])*] $constname : $consttype;)*]);
+    };
+    (INTERNAL: TRAIT CONSTS, [$($callback:tt)*], [$($consts:tt)*],
+     {
+         $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty $(= $constdefault:expr)*;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT CONSTS, [$($callback)*],
+                [$($consts)* [$(#[$($cattr)*])*] $constname : $consttype;],
+                { $($body)* });
+    };
+    (INTERNAL: TRAIT CONSTS, [$($callback:tt)*], [$($consts:tt)*], {}) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* [$($consts)*]);
+    };
+    // skip any other item, see next-item
+    (INTERNAL: TRAIT CONSTS, $callback:tt, $consts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [TRAIT CONSTS, $callback, $consts,], [], $body);
+    };
+    (INTERNAL: TRAIT CONSTS, $callback:tt, $consts:tt, $item:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT CONSTS, $callback, $consts, $body);
+    };
+
+    // trait-defaults: same as trait-consts, keeping the defaults of the consts which have one, as
+    //    [[attrs] NAME: Type = default; ...]
+    // trait-defaults-fast: a body of nothing but consts is collected at once
+    (INTERNAL: TRAIT DEFAULTS, [$($callback:tt)*], [],
+     {
+         $($(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty $(= $constdefault:expr)*;)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* [$([$(#[$($cattr)*])*] $constname : $consttype $(= $constdefault)*;)*]);
+    };
+    (INTERNAL: TRAIT DEFAULTS, [$($callback:tt)*], [$($consts:tt)*],
+     {
+         $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty $(= $constdefault:expr)*;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT DEFAULTS, [$($callback)*],
+                [$($consts)* [$(#[$($cattr)*])*] $constname : $consttype $(= $constdefault)*;],
+                { $($body)* });
+    };
+    (INTERNAL: TRAIT DEFAULTS, [$($callback:tt)*], [$($consts:tt)*], {}) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* [$($consts)*]);
+    };
+    // trait-defaults-fn: skip a simple method at once, as in parse-trait-fn (the other options add
+    //    some, which fingerprint may come after)
+    (INTERNAL: TRAIT DEFAULTS, $callback:tt, $consts:tt,
+     {
+         $(#[$fattr:meta])* fn $fname:ident $args:tt $(-> $ret:ty)* $(where Self: $sized:ident)* { $($fbody:tt)* }
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT DEFAULTS, $callback, $consts, { $($body)* });
+    };
+    (INTERNAL: TRAIT DEFAULTS, $callback:tt, $consts:tt,
+     {
+         $(#[$fattr:meta])* fn $fname:ident $args:tt $(-> $ret:ty)* $(where Self: $sized:ident)*;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT DEFAULTS, $callback, $consts, { $($body)* });
+    };
+    // skip any other item, see next-item
+    (INTERNAL: TRAIT DEFAULTS, $callback:tt, $consts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [TRAIT DEFAULTS, $callback, $consts,], [], $body);
+    };
+    (INTERNAL: TRAIT DEFAULTS, $callback:tt, $consts:tt, $item:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT DEFAULTS, $callback, $consts, $body);
+    };
+
+    // def-trait: start going through the items of a trait, one at a time
+    // the items are moved into the square brackets after the header once they have been seen, with
+    //    the consts turned into static functions, so that the rest of the body gets shorter at every
+    //    step and nothing is looked at twice; when the body is empty, all of the items have been seen
+    (INTERNAL: DEFINE TRAIT, $attrs:tt $before:tt $traitname:tt, { $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname, [], { $($body)* });
+    };
+    // parse-trait-fn-attrs: move the attributes given by the fn_attr marker of a const after its
+    //    other attributes (including any other guilty markers), so that they only end up on the
+    //    function
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, $done:tt,
+     {
+         #[guilty(fn_attr($($fattr:meta),*))] $(#[$($cattr:tt)*])* const $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname, $done,
+                { $(#[$($cattr)*])* $(#[$fattr])* const $($body)* });
+    };
+    // parse-trait-noreflect: drop the no_reflect marker from a const (see reflected)
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, $done:tt,
+     {
+         #[guilty(no_reflect)] $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname, $done, { $($body)* });
+    };
+    // parse-trait-typenum: declare the associated type named by the typenum option of a const
+    //    (gated like it, see cfg-attrs), then drop the marker
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, $done:tt,
+     {
+         #[guilty(typenum = $tyname:ident)] $(#[$($cattr:tt)*])* const $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ATTRS,
+                        [TYPENUM TYPE, [TRAIT BODY, $attrs $before $traitname,], $done,
+                         [#[allow(non_camel_case_types)] type $tyname: ::typenum::Unsigned;], { $(#[$($cattr)*])* const $($body)* },],
+                        [], [$(#[$($cattr)*])*]);
+    };
+    (INTERNAL: TYPENUM TYPE, [$($stage:tt)*], [$($done:tt)*], [$($item:tt)*], $body:tt, [$($cfgs:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: $($stage)* [$($done)* $($cfgs)* $($item)*], $body);
+    };
+    // parse-trait-per-variant: parse a const with the per_variant option, which becomes a method
+    //    taking &self (with the default, if any, as its body)
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         #[guilty(per_variant)] $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constdefault:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] fn __guilty_mangle $constname(&self) -> $consttype { $constdefault }
+                ],
+                { $($body)* });
+    };
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         #[guilty(per_variant)] $(#[$cattr:meta])* const $constname:ident : $consttype:ty;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] fn __guilty_mangle $constname(&self) -> $consttype;
+                ],
+                { $($body)* });
+    };
+    // parse-trait-litconst: same as parse-trait-defconst, for a default which is a literal (which
+    //    is added to the docs)
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constdefault:literal;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[doc = ""] #[doc = concat!("Default: `", stringify!($constdefault), "`")]
+                    #[allow(non_snake_case)] fn __guilty_mangle $constname() -> $consttype { $constdefault }
+                ],
+                { $($body)* });
+    };
+    // parse-trait-defconst: parse a const (that has a default value)
+    // the square brackets contain [attrs] [trait] [Trait] or [attrs] [pub trait] [Trait]
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constdefault:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] fn __guilty_mangle $constname() -> $consttype { $constdefault }
+                ],
+                { $($body)* });
+    };
+    // parse-trait-nodefconst: parse a const (that has no default value)
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* const $constname:ident : $consttype:ty;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] fn __guilty_mangle $constname() -> $consttype;
+                ],
+                { $($body)* });
+    };
+    // parse-trait-static: parse a static into a function returning a reference to it (a static in
+    //    a trait has no value, since each impl has its own)
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* static $staticname:ident : $statictype:ty;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] fn __guilty_mangle $staticname() -> &'static $statictype;
+                ],
+                { $($body)* });
+    };
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, $done:tt,
+     {
+         $(#[$cattr:meta])* static $staticname:ident : $($body:tt)*
+     }) => {
+        compile_error!(concat!("guilty!: static `", stringify!($staticname),
+                               "` can't have a value in a trait, it must be defined by each impl"));
+    };
+    // parse-trait-fn: fast path for a simple method (such as the functions object-safe-const
+    //    makes of the consts), which is passed through (anything fancier goes through
+    //    parse-trait-item)
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$fattr:meta])* fn __guilty_mangle $fname:ident $args:tt $(-> $ret:ty)* $(where Self: $sized:ident)* { $($fbody:tt)* }
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$fattr])* fn __guilty_mangle $fname $args $(-> $ret)* $(where Self: $sized)* { $($fbody)* }
+                ],
+                { $($body)* });
+    };
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$fattr:meta])* fn __guilty_mangle $fname:ident $args:tt $(-> $ret:ty)* $(where Self: $sized:ident)*;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$fattr])* fn __guilty_mangle $fname $args $(-> $ret)* $(where Self: $sized)*;
+                ],
+                { $($body)* });
+    };
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$fattr:meta])* fn $fname:ident $args:tt $(-> $ret:ty)* $(where Self: $sized:ident)* { $($fbody:tt)* }
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$fattr])* fn $fname $args $(-> $ret)* $(where Self: $sized)* { $($fbody)* }
+                ],
+                { $($body)* });
+    };
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*],
+     {
+         $(#[$fattr:meta])* fn $fname:ident $args:tt $(-> $ret:ty)* $(where Self: $sized:ident)*;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname,
+                [
+                    $($done)*
+                    $(#[$fattr])* fn $fname $args $(-> $ret)* $(where Self: $sized)*;
+                ],
+                { $($body)* });
+    };
+    // def-trait-done: all items are seen, output the trait
+    // indirection through __guilty_mangle, as in item-redir (but directly, since the trait may be
+    //    close to the recursion limit)
+    (INTERNAL: TRAIT BODY, [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*], [$($done:tt)*], {}) => {
+        $crate::__guilty_mangle! { [] $(#[$attr])* $($before)+ $($traitname)* { $($done)* } }
+    };
+    // parse-trait-item: any other item is passed through, see next-item
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, $done:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [TRAIT BODY, $attrs $before $traitname, $done,], [], $body);
+    };
+    (INTERNAL: TRAIT BODY, $attrs:tt $before:tt $traitname:tt, [$($done:tt)*], [$($item:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: TRAIT BODY, $attrs $before $traitname, [$($done)* $($item)*], $body);
+    };
+
+    // impl-opt-layout: the auto_layout option synthesizes SIZE and ALIGN consts from the layout of
+    //    the implementing type, as if they had been written at the beginning of the impl
+    (INTERNAL: IMPL OPTIONS, [auto_layout, $($opts:tt)*], [$generics:tt $traitname:tt [$($structname:tt)*] $where:tt],
+     {
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, [$($opts)*], [$generics $traitname [$($structname)*] $where],
+                {
+                    const SIZE: usize = $crate::__core::mem::size_of::<$($structname)*>();
+                    const ALIGN: usize = $crate::__core::mem::align_of::<$($structname)*>();
+                    $($body)*
+                });
+    };
+    // impl-opt-fingerprint: the fingerprint option hashes the names, types and values of the consts
+    //    defined in the impl into a CONSTS_FINGERPRINT const, starting from the hash of the trait
+    //    (see trait-fingerprint-def)
+    // calls on to impl-consts (with a copy of the body) to collect the consts for fingerprint-def
+    // an inherent impl has no trait to start from
+    (INTERNAL: IMPL OPTIONS, [fingerprint, $($opts:tt)*], [$generics:tt [] $structname:tt $where:tt], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL CONSTS,
+                [REFLECTED, [CFG ONLY, [FINGERPRINT, [$($opts)*] [$generics [] $structname $where] $body [$crate::__fingerprint("")],], [],], [],],
+                [], $body);
+    };
+    (INTERNAL: IMPL OPTIONS, [fingerprint, $($opts:tt)*], [$generics:tt [$($traitname:tt)*] $structname:tt $where:tt], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL CONSTS,
+                [REFLECTED, [CFG ONLY, [FINGERPRINT, [$($opts)*] [$generics [$($traitname)*] $structname $where] $body
+                                        [<Self as $($traitname)*>::__guilty_trait_fingerprint()],], [],], [],],
+                [], $body);
+    };
+    // fingerprint-def: add the fingerprint, then continue with the options
+    (INTERNAL: FINGERPRINT, $opts:tt $parts:tt
+     {
+         $($body:tt)*
+     }
+     [$($start:tt)*],
+     [$([$(#[$cattr:meta])*] $constname:ident : $consttype:ty = $constvalue:expr;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, $opts, $parts,
+                {
+                    const CONSTS_FINGERPRINT: u64 = {
+                        #[allow(unused_mut)]
+                        let mut hash = $($start)*;
+                        $($(#[$cattr])* {
+                            hash = $crate::__fingerprint_extend(hash, concat!(
+                                stringify!($constname), ":", stringify!($consttype), "=", stringify!($constvalue), ";"));
+                        })*
+                        hash
+                    };
+                    $($body)*
+                });
+    };
+    // impl-opt-overrides: the track_overrides option records which consts the impl defines itself
+    //    (as opposed to inheriting the default from the trait), see check-overridden
+    // calls on to impl-consts (with a copy of the body) to collect the consts for overrides-def
+    (INTERNAL: IMPL OPTIONS, [track_overrides, $($opts:tt)*], $parts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL CONSTS, [REFLECTED, [OVERRIDES, [$($opts)*] $parts $body,], [],], [], $body);
+    };
+    // overrides-def: append the list of defined consts, then continue with the options
+    (INTERNAL: OVERRIDES, $opts:tt $parts:tt
+     {
+         $($body:tt)*
+     },
+     [$([$(#[$cattr:meta])*] $constname:ident : $consttype:ty = $constvalue:expr;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, $opts, $parts,
+                {
+                    $($body)*
+                    fn __guilty_overridden() -> &'static [&'static str] {
+                        &[$(stringify!($constname)),*]
+                    }
+                });
+    };
+    // impl-opt-provenance: the provenance option records where the consts defined in the impl are
+    //    defined, deferring to the trait for the others (see trait-opt-provenance)
+    // calls on to impl-consts (with a copy of the body) to collect the consts for impl-provenance-def
+    (INTERNAL: IMPL OPTIONS, [provenance, $($opts:tt)*], $parts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL CONSTS, [REFLECTED, [IMPL PROVENANCE, [$($opts)*] $parts $body,], [],], [], $body);
+    };
+    // impl-provenance-def: append the method, then continue with the options
+    (INTERNAL: IMPL PROVENANCE, $opts:tt $parts:tt
+     {
+         $($body:tt)*
+     },
+     [$([$(#[$cattr:meta])*] $constname:ident : $consttype:ty = $constvalue:expr;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, $opts, $parts,
+                {
+                    $($body)*
+                    fn __guilty_location(name: &str) -> $crate::__core::option::Option<(&'static str, u32)> {
+                        if [$(stringify!($constname)),*].contains(&name) {
+                            $crate::__guilty_location!()
+                        } else {
+                            Self::__guilty_trait_location(name)
+                        }
+                    }
+                });
+    };
+    // impl-opt-literals-mod: the literals_mod option also defines the consts of the impl whose
+    //    values are literals as real consts, in the given module, and as the arms of a macro of the
+    //    same name, which expands to the literal itself
+    // calls on to literals (with a copy of the body) to collect them for literals-def
+    (INTERNAL: IMPL OPTIONS, [literals_mod = $m:ident, $($opts:tt)*], $parts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, [$m [$($opts)*] $parts $body], [], [], $body);
+    };
+    // literals: skip a const with the env option, whose value is only known at runtime
+    (INTERNAL: LITERALS, $state:tt, $literals:tt, $arms:tt,
+     {
+         #[guilty(env = $var:expr)] $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, $state, $literals, $arms, { $($body)* });
+    };
+    // literals-cached: skip a const with the cached option, whose value has a different type
+    (INTERNAL: LITERALS, $state:tt, $literals:tt, $arms:tt,
+     {
+         #[guilty(cached)] $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, $state, $literals, $arms, { $($body)* });
+    };
+    // literals-per-variant: skip a const with the per_variant option, whose value depends on self
+    (INTERNAL: LITERALS, $state:tt, $literals:tt, $arms:tt,
+     {
+         #[guilty(per_variant)] $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, $state, $literals, $arms, { $($body)* });
+    };
+    // literals-typenum: drop the typenum marker
+    (INTERNAL: LITERALS, $state:tt, $literals:tt, $arms:tt,
+     {
+         #[guilty(typenum = $tyname:ident)] $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, $state, $literals, $arms, { $($body)* });
+    };
+    // literals-static: drop the static marker (the value is still a literal)
+    (INTERNAL: LITERALS, $state:tt, $literals:tt, $arms:tt,
+     {
+         #[guilty(static)] $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, $state, $literals, $arms, { $($body)* });
+    };
+    // literals-fn-attrs: drop the fn_attr marker, whose attributes are not for the real consts
+    (INTERNAL: LITERALS, $state:tt, $literals:tt, $arms:tt,
+     {
+         #[guilty(fn_attr $fattrs:tt)] $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, $state, $literals, $arms, { $($body)* });
+    };
+    // literals-impl-default: drop the impl_default marker
+    (INTERNAL: LITERALS, $state:tt, $literals:tt, $arms:tt,
+     {
+         #[guilty(impl_default)] $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, $state, $literals, $arms, { $($body)* });
+    };
+    // literals-noreflect: drop the no_reflect marker
+    (INTERNAL: LITERALS, $state:tt, $literals:tt, $arms:tt,
+     {
+         #[guilty(no_reflect)] $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, $state, $literals, $arms, { $($body)* });
+    };
+    // literals-const: collect a const whose value is a literal, and (unless it has attributes
+    //    other than docs, which could not be put on the arm) its macro arm
+    (INTERNAL: LITERALS, $state:tt, [$($literals:tt)*], [$($arms:tt)*],
+     {
+         $(#[doc $($doc:tt)*])* const $constname:ident : $consttype:ty = $constvalue:literal;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, $state,
+                        [$($literals)* $(#[doc $($doc)*])* pub const $constname : $consttype = $constvalue;],
+                        [$($arms)* ($constname) => { $constvalue };],
+                        { $($body)* });
+    };
+    (INTERNAL: LITERALS, $state:tt, [$($literals:tt)*], $arms:tt,
+     {
+         $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constvalue:literal;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, $state,
+                        [$($literals)* $(#[$cattr])* pub const $constname : $consttype = $constvalue;],
+                        $arms,
+                        { $($body)* });
+    };
+    // literals-done: output the module and the macro (if it has any arms), then continue with the
+    //    options
+    (INTERNAL: LITERALS, [$m:ident $opts:tt $parts:tt $body:tt], [$($literals:tt)*], [], {}) => {
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        pub mod $m {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $($literals)*
+        }
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, $opts, $parts, $body);
+    };
+    (INTERNAL: LITERALS, [$m:ident $opts:tt $parts:tt $body:tt], [$($literals:tt)*], [$($arms:tt)+], {}) => {
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        pub mod $m {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $($literals)*
+        }
+        #[allow(unused_macros)]
+        macro_rules! $m {
+            $($arms)+
+        }
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, $opts, $parts, $body);
+    };
+    // skip any other const or item, see next-item
+    (INTERNAL: LITERALS, $state:tt, $literals:tt, $arms:tt,
+     {
+         $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, $state, $literals, $arms, { $($body)* });
+    };
+    (INTERNAL: LITERALS, $state:tt, $literals:tt, $arms:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [LITERALS, $state, $literals, $arms,], [], $body);
+    };
+    (INTERNAL: LITERALS, $state:tt, $literals:tt, $arms:tt, $item:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: LITERALS, $state, $literals, $arms, $body);
+    };
+    // impl-opt-inherent: the inherent option mirrors the consts defined in the impl as inherent
+    //    functions of the implementing type, with the given visibility (private if there is none)
+    // calls on to impl-consts (with a copy of the body) to collect the consts for inherent-def
+    (INTERNAL: IMPL OPTIONS, [inherent = $vis:vis, $($opts:tt)*], $parts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL CONSTS, [CFG ONLY, [INHERENT, [$($opts)*] $parts [$vis] $body,], [],], [], $body);
+    };
+    (INTERNAL: IMPL OPTIONS, [inherent, $($opts:tt)*], $parts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL CONSTS, [CFG ONLY, [INHERENT, [$($opts)*] $parts [] $body,], [],], [], $body);
+    };
+    // inherent-def: output the inherent impl, then continue with the options
+    (INTERNAL: INHERENT, $opts:tt [[$($generics:tt)*] [$($traitname:tt)*] [$($structname:tt)*] [$($where:tt)*]] [$($vis:tt)*] $body:tt,
+     $consts:tt) => {
+        $crate::__guilty_rules!(INTERNAL: INHERENT IMPL, $opts [$($generics)*] [$($where)*] $($structname)*, $($traitname)*, $($vis)* $consts);
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, $opts, [[$($generics)*] [$($traitname)*] [$($structname)*] [$($where)*]], $body);
+    };
+    // the options are the mode of item-redir for the inherent impl
+    (INTERNAL: INHERENT IMPL, $opts:tt [$($generics:tt)*] [$($where:tt)*] $structty:ty, $traitpath:path, $vis:vis
+     [$([$(#[$cattr:meta])*] $constname:ident : $consttype:ty = $constvalue:expr;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: AS ITEM, $opts
+                impl<$($generics)*> $structty $($where)* {
+                    $(
+                        $(#[$cattr])*
+                        #[doc = concat!("Same as `<", stringify!($structty), " as ", stringify!($traitpath), ">::",
+                                        stringify!($constname), "`")]
+                        #[allow(non_snake_case, dead_code)]
+                        $vis fn __guilty_mangle $constname() -> $consttype {
+                            <$structty as $traitpath>::__guilty_mangle $constname()
+                        }
+                    )*
+                });
+    };
+    // impl-opt-also-for-ref: the also_for_ref option implements the trait for &Struct as well (or
+    //    for those of &Struct, &mut Struct and Box<Struct> which are listed), forwarding to the
+    //    impl for Struct
+    // calls on to forward-item (with a copy of the body) to build the forwarding impl body
+    (INTERNAL: IMPL OPTIONS, [also_for_ref, $($opts:tt)*], $parts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD START, [[$($opts)*] $parts [ref] $body], $parts);
+    };
+    (INTERNAL: IMPL OPTIONS, [also_for_ref($($wrapper:ident),*), $($opts:tt)*], $parts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD START, [[$($opts)*] $parts [$($wrapper)*] $body], $parts);
+    };
+    // forward-start: spell out the qualified path to the items of the impl, then go on to
+    //    forward-item
+    (INTERNAL: FORWARD START, [$opts:tt $parts:tt $wrappers:tt $orig:tt],
+     [[$($generics:tt)*] [$($traitname:tt)*] [$($structname:tt)*] [$($where:tt)*]]) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD, [$opts $parts $wrappers $orig [<$($structname)* as $($traitname)*>]],
+                        [], [], $orig);
+    };
+    // forward-typenum: forward the associated type named by the typenum option of a const (as in
+    //    forward-type), then drop the marker
+    (INTERNAL: FORWARD, [$opts:tt $parts:tt $wrappers:tt $orig:tt [$($qself:tt)*]], $consts:tt, [$($items:tt)*],
+     {
+         #[guilty(typenum = $tyname:ident)] $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD, [$opts $parts $wrappers $orig [$($qself)*]], $consts,
+                        [$($items)* type $tyname = $($qself)*::$tyname;],
+                        { $($body)* });
+    };
+    // forward-per-variant: forward a const with the per_variant option as a &self method (with
+    //    its cfg attributes)
+    (INTERNAL: FORWARD, $state:tt, $consts:tt, $items:tt,
+     {
+         #[guilty(per_variant)] $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ATTRS,
+                        [FORWARD VARIANT CONST, $state, $consts, $items, { $($body)* }, $constname : $consttype,],
+                        [], [$(#[$($cattr)*])*]);
+    };
+    (INTERNAL: FORWARD VARIANT CONST, [$opts:tt $parts:tt $wrappers:tt $orig:tt [$($qself:tt)*]], $consts:tt, [$($items:tt)*],
+     $body:tt, $constname:ident : $consttype:ty, [$($cfgs:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD, [$opts $parts $wrappers $orig [$($qself)*]], $consts,
+                        [$($items)*
+                         $($cfgs)* #[allow(non_snake_case)] fn __guilty_mangle $constname(&self) -> $consttype {
+                             $($qself)*::__guilty_mangle $constname(&**self)
+                         }],
+                        $body);
+    };
+    // forward-const: forward a const (with its cfg attributes, see cfg-attrs)
+    (INTERNAL: FORWARD, $state:tt, $consts:tt, $items:tt,
+     {
+         $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ATTRS, [FORWARD CONST, $state, $consts, $items, { $($body)* }, $constname : $consttype,],
+                        [], [$(#[$($cattr)*])*]);
+    };
+    (INTERNAL: FORWARD CONST, [$opts:tt $parts:tt $wrappers:tt $orig:tt [$($qself:tt)*]], [$($consts:tt)*], $items:tt,
+     $body:tt, $constname:ident : $consttype:ty, [$($cfgs:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD, [$opts $parts $wrappers $orig [$($qself)*]],
+                        [$($consts)* $($cfgs)* const $constname: $consttype
+                         = $crate::__guilty_mangle!([__guilty_mangle unmangled] $($qself)*::__guilty_mangle $constname());],
+                        $items, $body);
+    };
+    // forward-type: forward an associated type
+    (INTERNAL: FORWARD, [$opts:tt $parts:tt $wrappers:tt $orig:tt [$($qself:tt)*]], $consts:tt, [$($items:tt)*],
+     {
+         $(#[$tattr:meta])* type $tyname:ident = $ty:ty;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD, [$opts $parts $wrappers $orig [$($qself)*]], $consts,
+                        [$($items)* $(#[$tattr])* type $tyname = $($qself)*::$tyname;],
+                        { $($body)* });
+    };
+    // forward-ref-method: forward a &self method
+    (INTERNAL: FORWARD, [$opts:tt $parts:tt $wrappers:tt $orig:tt [$($qself:tt)*]], $consts:tt, [$($items:tt)*],
+     {
+         $(#[$fattr:meta])* fn $fname:ident(&self $(, $arg:ident : $argtype:ty)* $(,)*) $(-> $ret:ty)* $fbody:block
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD, [$opts $parts $wrappers $orig [$($qself)*]], $consts,
+                        [$($items)*
+                         $(#[$fattr])* fn $fname(&self $(, $arg: $argtype)*) $(-> $ret)* {
+                             $($qself)*::$fname(&**self $(, $arg)*)
+                         }],
+                        { $($body)* });
+    };
+    // forward-mut-method: forward a &mut self method (not possible for &Struct, so it is an error
+    //    if ref is one of the wrappers)
+    (INTERNAL: FORWARD, [$opts:tt $parts:tt $wrappers:tt $orig:tt [$($qself:tt)*]], $consts:tt, [$($items:tt)*],
+     {
+         $(#[$fattr:meta])* fn $fname:ident(&mut self $(, $arg:ident : $argtype:ty)* $(,)*) $(-> $ret:ty)* $fbody:block
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD MUT, $wrappers, $fname,
+                        [INTERNAL: FORWARD, [$opts $parts $wrappers $orig [$($qself)*]], $consts,
+                         [$($items)*
+                          $(#[$fattr])* fn $fname(&mut self $(, $arg: $argtype)*) $(-> $ret)* {
+                              $($qself)*::$fname(&mut **self $(, $arg)*)
+                          }],
+                         { $($body)* }]);
+    };
+    (INTERNAL: FORWARD MUT, [ref $($wrappers:ident)*], $fname:ident, $next:tt) => {
+        compile_error!(concat!("guilty!: also_for_ref can't forward the `&mut self` method `", stringify!($fname),
+                               "` to a shared reference (list the other wrappers instead, as in `also_for_ref(mut, box)`)"));
+    };
+    (INTERNAL: FORWARD MUT, [$wrapper:ident $($wrappers:ident)*], $fname:ident, $next:tt) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD MUT, [$($wrappers)*], $fname, $next);
+    };
+    (INTERNAL: FORWARD MUT, [], $fname:ident, [$($next:tt)*]) => {
+        $crate::__guilty_rules!($($next)*);
+    };
+    // forward-static-fn: forward a function without a receiver
+    (INTERNAL: FORWARD, [$opts:tt $parts:tt $wrappers:tt $orig:tt [$($qself:tt)*]], $consts:tt, [$($items:tt)*],
+     {
+         $(#[$fattr:meta])* fn $fname:ident($($arg:ident : $argtype:ty),* $(,)*) $(-> $ret:ty)* $fbody:block
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD, [$opts $parts $wrappers $orig [$($qself)*]], $consts,
+                        [$($items)*
+                         $(#[$fattr])* fn $fname($($arg: $argtype),*) $(-> $ret)* {
+                             $($qself)*::$fname($($arg),*)
+                         }],
+                        { $($body)* });
+    };
+    // forward-static: forward a static, through the function returning a reference to it (see
+    //    parse-impl-static)
+    (INTERNAL: FORWARD, [$opts:tt $parts:tt $wrappers:tt $orig:tt [$($qself:tt)*]], $consts:tt, [$($items:tt)*],
+     {
+         $(#[$sattr:meta])* static $staticname:ident : $statictype:ty = $staticvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD, [$opts $parts $wrappers $orig [$($qself)*]], $consts,
+                        [$($items)*
+                         $(#[$sattr])* #[allow(non_snake_case)] fn __guilty_mangle $staticname() -> &'static $statictype {
+                             $($qself)*::__guilty_mangle $staticname()
+                         }],
+                        { $($body)* });
+    };
+    // forward-unsupported: any other method (say, with generics or a where clause) or item can't
+    //    be forwarded
+    (INTERNAL: FORWARD, $state:tt, $consts:tt, $items:tt,
+     {
+         $(#[$($fattr:tt)*])* fn $fname:ident $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD UNSUPPORTED, ["method `", stringify!($fname), "`"]);
+    };
+    (INTERNAL: FORWARD, $state:tt, $consts:tt, $items:tt,
+     {
+         $(#[$($fattr:tt)*])* $qualifier:ident fn $fname:ident $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD UNSUPPORTED, [stringify!($qualifier), " method `", stringify!($fname), "`"]);
+    };
+    (INTERNAL: FORWARD, $state:tt, $consts:tt, $items:tt, { $($body:tt)+ }) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [FORWARD UNSUPPORTED,], [], { $($body)+ });
+    };
+    (INTERNAL: FORWARD UNSUPPORTED, [$($item:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD UNSUPPORTED, ["`", stringify!($($item)*), "`"]);
+    };
+    (INTERNAL: FORWARD UNSUPPORTED, [$($item:tt)*]) => {
+        compile_error!(concat!("guilty!: also_for_ref can't forward ", $($item)*,
+                               " (only consts, statics, associated types, and methods with a `&self`, `&mut self` or",
+                               " no receiver, plain argument names and no generics or where clause)"));
+    };
+    // forward-done: output the forwarding impls, then continue with the options (which are the
+    //    mode of item-redir for the forwarding impls)
+    (INTERNAL: FORWARD, [$opts:tt $parts:tt $wrappers:tt $orig:tt $qself:tt], $consts:tt, $items:tt, {}) => {
+        $crate::__guilty_rules!(INTERNAL: FORWARD IMPLS, $opts $parts $consts $items, $wrappers);
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, $opts, $parts, $orig);
+    };
+    // forward-impls: output the forwarding impl for each wrapper type
+    (INTERNAL: FORWARD IMPLS, $opts:tt [[$($generics:tt)*] [$($traitname:tt)*] [$($structname:tt)*] [$($where:tt)*]]
+     [$($consts:tt)*] [$($items:tt)*], [ref $($wrappers:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: DEFINE IMPL,
+                        [#[__guilty_mangle $opts] impl<'guilty_ref, $($generics)*> $($traitname)* for &'guilty_ref $($structname)* $($where)*],
+                        { $($consts)* $($items)* });
+        $crate::__guilty_rules!(INTERNAL: FORWARD IMPLS, $opts [[$($generics)*] [$($traitname)*] [$($structname)*] [$($where)*]]
+                        [$($consts)*] [$($items)*], [$($wrappers)*]);
+    };
+    (INTERNAL: FORWARD IMPLS, $opts:tt [[$($generics:tt)*] [$($traitname:tt)*] [$($structname:tt)*] [$($where:tt)*]]
+     [$($consts:tt)*] [$($items:tt)*], [mut $($wrappers:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: DEFINE IMPL,
+                        [#[__guilty_mangle $opts] impl<'guilty_ref, $($generics)*> $($traitname)* for &'guilty_ref mut $($structname)* $($where)*],
+                        { $($consts)* $($items)* });
+        $crate::__guilty_rules!(INTERNAL: FORWARD IMPLS, $opts [[$($generics)*] [$($traitname)*] [$($structname)*] [$($where)*]]
+                        [$($consts)*] [$($items)*], [$($wrappers)*]);
+    };
+    (INTERNAL: FORWARD IMPLS, $opts:tt [[$($generics:tt)*] [$($traitname:tt)*] [$($structname:tt)*] [$($where:tt)*]]
+     [$($consts:tt)*] [$($items:tt)*], [box $($wrappers:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: DEFINE IMPL,
+                        [#[__guilty_mangle $opts] impl<$($generics)*> $($traitname)* for Box<$($structname)*> $($where)*],
+                        { $($consts)* $($items)* });
+        $crate::__guilty_rules!(INTERNAL: FORWARD IMPLS, $opts [[$($generics)*] [$($traitname)*] [$($structname)*] [$($where)*]]
+                        [$($consts)*] [$($items)*], [$($wrappers)*]);
+    };
+    (INTERNAL: FORWARD IMPLS, $opts:tt $parts:tt $consts:tt $items:tt, []) => {};
+    // impl-opt-check-invariants: the check_invariants option makes the consts defined in the impl
+    //    call the hook of the trait's invariants (see invariants-def) before returning their value
+    // calls on to check-invariants-const, which rewrites the consts
+    (INTERNAL: IMPL OPTIONS, [check_invariants, $($opts:tt)*], [$generics:tt [] $structname:tt $where:tt], $body:tt) => {
+        compile_error!("guilty!: the check_invariants option is for trait impls");
+    };
+    (INTERNAL: IMPL OPTIONS, [check_invariants, $($opts:tt)*], $parts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: CHECK INVARIANTS, [$($opts)*] $parts, {}, $body);
+    };
+    // check-invariants-per-variant: move a const with the per_variant option as it is (see
+    //    overridable-per-variant)
+    (INTERNAL: CHECK INVARIANTS, $opts:tt $parts:tt,
+     {
+         $($scanned:tt)*
+     },
+     {
+         #[guilty(per_variant)] $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = match $selfname:ident $arms:tt;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: CHECK INVARIANTS, $opts $parts,
+                {
+                    $($scanned)*
+                    #[guilty(per_variant)] $(#[$($cattr)*])* const $constname : $consttype = match $selfname $arms;
+                },
+                { $($body)* });
+    };
+    // check-invariants-const: move a const from the body to the rewritten part
+    (INTERNAL: CHECK INVARIANTS, $opts:tt [$generics:tt [$($traitname:tt)*] $structname:tt $where:tt],
+     {
+         $($scanned:tt)*
+     },
+     {
+         $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: CHECK INVARIANTS, $opts [$generics [$($traitname)*] $structname $where],
+                {
+                    $($scanned)*
+                    $(#[$($cattr)*])* const $constname : $consttype = {
+                        <Self as $($traitname)*>::__guilty_check_invariants();
+                        $constvalue
+                    };
+                },
+                {
+                    $($body)*
+                });
+    };
+    // check-invariants-done: all items are seen, continue with the options
+    (INTERNAL: CHECK INVARIANTS, $opts:tt $parts:tt, $scanned:tt, {}) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, $opts, $parts, $scanned);
+    };
+    // check-invariants-item: move any other item to the rewritten part, see next-item
+    (INTERNAL: CHECK INVARIANTS, $opts:tt $parts:tt, $scanned:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [CHECK INVARIANTS, $opts $parts, $scanned,], [], $body);
+    };
+    (INTERNAL: CHECK INVARIANTS, $opts:tt $parts:tt, { $($scanned:tt)* }, [$($item:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: CHECK INVARIANTS, $opts $parts, { $($scanned)* $($item)* }, $body);
+    };
+    // impl-opt-overridable: the overridable option makes the consts defined in the impl consult the
+    //    thread-local overrides (see ConstOverride) before returning their value
+    // calls on to overridable-const, which rewrites the consts
+    (INTERNAL: IMPL OPTIONS, [overridable, $($opts:tt)*], $parts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: OVERRIDABLE, [$($opts)*] $parts, {}, $body);
+    };
+    // overridable-per-variant: move a const with the per_variant option as it is (it can't be
+    //    overridden, having a value per instance)
+    (INTERNAL: OVERRIDABLE, $opts:tt $parts:tt,
+     {
+         $($scanned:tt)*
+     },
+     {
+         #[guilty(per_variant)] $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = match $selfname:ident $arms:tt;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: OVERRIDABLE, $opts $parts,
+                {
+                    $($scanned)*
+                    #[guilty(per_variant)] $(#[$($cattr)*])* const $constname : $consttype = match $selfname $arms;
+                },
+                { $($body)* });
+    };
+    // overridable-const: move a const from the body to the rewritten part
+    (INTERNAL: OVERRIDABLE, $opts:tt [$generics:tt $traitname:tt [$($structname:tt)*] $where:tt],
+     {
+         $($scanned:tt)*
+     },
+     {
+         $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: OVERRIDABLE, $opts [$generics $traitname [$($structname)*] $where],
+                {
+                    $($scanned)*
+                    $(#[$($cattr)*])* const $constname : $consttype =
+                        match $crate::__override_get::<$($structname)*, $consttype>(stringify!($constname)) {
+                            $crate::__core::option::Option::Some(value) => value,
+                            $crate::__core::option::Option::None => $constvalue,
+                        };
+                },
+                {
+                    $($body)*
+                });
+    };
+    // overridable-done: all items are seen, continue with the options
+    (INTERNAL: OVERRIDABLE, $opts:tt $parts:tt, $scanned:tt, {}) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, $opts, $parts, $scanned);
+    };
+    // overridable-item: move any other item to the rewritten part, see next-item
+    (INTERNAL: OVERRIDABLE, $opts:tt $parts:tt, $scanned:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [OVERRIDABLE, $opts $parts, $scanned,], [], $body);
+    };
+    (INTERNAL: OVERRIDABLE, $opts:tt $parts:tt, { $($scanned:tt)* }, [$($item:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: OVERRIDABLE, $opts $parts, { $($scanned)* $($item)* }, $body);
+    };
+    // impl-opt-self-consts: the self_consts option rewrites Self::CONST in the method bodies into
+    //    accesses of the consts (see self-consts)
+    (INTERNAL: IMPL OPTIONS, [self_consts, $($opts:tt)*], $parts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SELF CONSTS, [IMPL OPTIONS, [$($opts)*], $parts,], [], $body);
+    };
+    // self-consts: go through the items of the body (moving them into the second square brackets,
+    //    as in def-trait), then pass the rewritten body on to the internal call given in the first
+    // the values of the consts and the bodies of the methods are rewritten by self-expr, which is
+    //    left in their place to be expanded once the trait or impl has been output, so that the
+    //    walks through them don't add up
+    // self-consts-per-variant: move a const with the per_variant option as it is (see
+    //    parse-impl-per-variant-const)
+    (INTERNAL: SELF CONSTS, $callback:tt, [$($done:tt)*],
+     {
+         #[guilty(per_variant)] $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = match $selfname:ident $arms:tt;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SELF CONSTS, $callback,
+                        [$($done)* #[guilty(per_variant)] $(#[$($cattr)*])* const $constname : $consttype
+                         = match $selfname $arms;],
+                        { $($body)* });
+    };
+    // self-consts-const: move a const, collecting its value up to the semicolon (unless it is a
+    //    literal, or a single token tree or a call)
+    (INTERNAL: SELF CONSTS, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty $(= $constvalue:literal)*;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SELF CONSTS, $callback,
+                        [$($done)* $(#[$($cattr)*])* const $constname : $consttype $(= $constvalue)*;], { $($body)* });
+    };
+    (INTERNAL: SELF CONSTS, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = $constvalue:tt;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SELF CONSTS, $callback,
+                        [$($done)* $(#[$($cattr)*])* const $constname : $consttype
+                         = $crate::__guilty_rules!(INTERNAL: SELF EXPR, [__guilty_mangle unmangled], $constvalue);],
+                        { $($body)* });
+    };
+    (INTERNAL: SELF CONSTS, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = $func:ident $args:tt;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SELF CONSTS, $callback,
+                        [$($done)* $(#[$($cattr)*])* const $constname : $consttype
+                         = $crate::__guilty_rules!(INTERNAL: SELF EXPR, [__guilty_mangle unmangled], $func $args);],
+                        { $($body)* });
+    };
+    (INTERNAL: SELF CONSTS, $callback:tt, $done:tt,
+     {
+         $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SELF VALUE, $callback, $done, [$(#[$($cattr)*])* const $constname : $consttype =], [],
+                        { $($body)* });
+    };
+    (INTERNAL: SELF VALUE, $callback:tt, [$($done:tt)*], [$($head:tt)*], [$($value:tt)*], { ; $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SELF CONSTS, $callback,
+                        [$($done)* $($head)* $crate::__guilty_rules!(INTERNAL: SELF EXPR, [__guilty_mangle unmangled], $($value)*);], { $($body)* });
+    };
+    (INTERNAL: SELF VALUE, $callback:tt, $done:tt, $head:tt, [$($value:tt)*], { $next:tt $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SELF VALUE, $callback, $done, $head, [$($value)* $next], { $($body)* });
+    };
+    // self-consts-fn: fast path for a simple method, whose body is rewritten as it is (anything
+    //    fancier goes through self-consts-item)
+    (INTERNAL: SELF CONSTS, $callback:tt, [$($done:tt)*],
+     {
+         $(#[$($fattr:tt)*])* fn $fname:ident $args:tt $(-> $ret:ty)* { $($fbody:tt)* }
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SELF CONSTS, $callback,
+                        [$($done)* $(#[$($fattr)*])* fn $fname $args $(-> $ret)* {
+                            $crate::__guilty_rules!(INTERNAL: SELF EXPR, [__guilty_mangle unmangled], $($fbody)*)
+                        }],
+                        { $($body)* });
+    };
+    // self-consts-done: all items have been seen
+    (INTERNAL: SELF CONSTS, [$($callback:tt)*], [$($done:tt)*], {}) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* { $($done)* });
+    };
+    // self-consts-item: split off any other item (see next-item), and rewrite its block, if any
+    //    (moving the rest of the item into the third square brackets, one token tree at a time),
+    //    with self-expr if the item is a method
+    (INTERNAL: SELF CONSTS, $callback:tt, $done:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [SELF CONSTS, $callback, $done,], [], $body);
+    };
+    (INTERNAL: SELF CONSTS, $callback:tt, $done:tt, $item:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SELF ITEM, $callback, $done, [], $item, $body);
+    };
+    (INTERNAL: SELF ITEM, $callback:tt, $done:tt, $head:tt, [{ $($block:tt)* }], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SELF WALK, [SELF ITEM DONE, $callback, $done, $head, $body,], [], [], $($block)*);
+    };
+    (INTERNAL: SELF ITEM, $callback:tt, [$($done:tt)*], [$($head:tt)*], [$last:tt], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SELF CONSTS, $callback, [$($done)* $($head)* $last], $body);
+    };
+    (INTERNAL: SELF ITEM, $callback:tt, $done:tt, [$($head:tt)*], [fn $($item:tt)+], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SELF FN, $callback, $done, [$($head)* fn], [$($item)+], $body);
+    };
+    (INTERNAL: SELF ITEM, $callback:tt, $done:tt, [$($head:tt)*], [$next:tt $($item:tt)+], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SELF ITEM, $callback, $done, [$($head)* $next], [$($item)+], $body);
+    };
+    (INTERNAL: SELF FN, $callback:tt, [$($done:tt)*], [$($head:tt)*], [{ $($block:tt)* }], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SELF CONSTS, $callback,
+                        [$($done)* $($head)* { $crate::__guilty_rules!(INTERNAL: SELF EXPR, [__guilty_mangle unmangled], $($block)*) }], $body);
+    };
+    (INTERNAL: SELF FN, $callback:tt, [$($done:tt)*], [$($head:tt)*], [$last:tt], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SELF CONSTS, $callback, [$($done)* $($head)* $last], $body);
+    };
+    (INTERNAL: SELF FN, $callback:tt, $done:tt, [$($head:tt)*], [$next:tt $($item:tt)+], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SELF FN, $callback, $done, [$($head)* $next], [$($item)+], $body);
+    };
+    (INTERNAL: SELF ITEM DONE, $callback:tt, [$($done:tt)*], [$($head:tt)*], $body:tt, [$($block:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: SELF CONSTS, $callback, [$($done)* $($head)* { $($block)* }], $body);
+    };
+    // self-expr: rewrite the value of a const or the body of a method, as a block
+    // the names in the block are mangled by item-redir according to the mode in the square
+    //    brackets, which is itself written as a name to be mangled by item-redir, since the
+    //    trait or impl has been output by the time the block is rewritten
+    (INTERNAL: SELF EXPR, $mode:tt, $($tokens:tt)*) => {
+        $crate::__guilty_rules!(INTERNAL: SELF WALK, [SELF EXPR DONE, $mode,], [], [], $($tokens)*)
+    };
+    (INTERNAL: SELF EXPR DONE, $mode:tt, [$($out:tt)*]) => {
+        $crate::__guilty_mangle!($mode { $($out)* })
+    };
+    // self-walk: rewrite Self::NAME, unless it is called or continued, one token tree at a time
+    //    (the second square brackets hold a stack of the groups the walk is in, with the output so
+    //    far and the rest of the input outside each of them, and the third the output so far in
+    //    the current group), then pass the output on to the internal call given in the first
+    //    square brackets, in square brackets
+    // the recursive calls are in braces, without a semicolon, so that the walk can also be used in
+    //    expressions (see self-expr)
+    (INTERNAL: SELF WALK, $callback:tt, $stack:tt, [$($out:tt)*], Self :: $name:ident ($($args:tt)*) $($rest:tt)*) => {
+        $crate::__guilty_rules! { INTERNAL: SELF WALK, $callback, $stack, [$($out)* Self::$name], ($($args)*) $($rest)* }
+    };
+    (INTERNAL: SELF WALK, $callback:tt, $stack:tt, [$($out:tt)*], Self :: $name:ident :: $($rest:tt)*) => {
+        $crate::__guilty_rules! { INTERNAL: SELF WALK, $callback, $stack, [$($out)* Self::$name], :: $($rest)* }
+    };
+    (INTERNAL: SELF WALK, $callback:tt, $stack:tt, [$($out:tt)*], Self :: $name:ident $($rest:tt)*) => {
+        $crate::__guilty_rules! { INTERNAL: SELF WALK, $callback, $stack, [$($out)* $crate::__guilty_mangle!([__guilty_mangle unmangled] Self::__guilty_mangle $name())],
+                                  $($rest)* }
+    };
+    (INTERNAL: SELF WALK, $callback:tt, [$($stack:tt)*], $out:tt, ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::__guilty_rules! { INTERNAL: SELF WALK, $callback, [[() $out [$($rest)*]] $($stack)*], [], $($inner)* }
+    };
+    (INTERNAL: SELF WALK, $callback:tt, [$($stack:tt)*], $out:tt, [$($inner:tt)*] $($rest:tt)*) => {
+        $crate::__guilty_rules! { INTERNAL: SELF WALK, $callback, [[[] $out [$($rest)*]] $($stack)*], [], $($inner)* }
+    };
+    (INTERNAL: SELF WALK, $callback:tt, [$($stack:tt)*], $out:tt, {$($inner:tt)*} $($rest:tt)*) => {
+        $crate::__guilty_rules! { INTERNAL: SELF WALK, $callback, [[{} $out [$($rest)*]] $($stack)*], [], $($inner)* }
+    };
+    (INTERNAL: SELF WALK, $callback:tt, $stack:tt, [$($out:tt)*], $token:tt $($rest:tt)*) => {
+        $crate::__guilty_rules! { INTERNAL: SELF WALK, $callback, $stack, [$($out)* $token], $($rest)* }
+    };
+    (INTERNAL: SELF WALK, $callback:tt, [[() [$($out:tt)*] [$($rest:tt)*]] $($stack:tt)*], [$($inner:tt)*],) => {
+        $crate::__guilty_rules! { INTERNAL: SELF WALK, $callback, [$($stack)*], [$($out)* ($($inner)*)], $($rest)* }
+    };
+    (INTERNAL: SELF WALK, $callback:tt, [[[] [$($out:tt)*] [$($rest:tt)*]] $($stack:tt)*], [$($inner:tt)*],) => {
+        $crate::__guilty_rules! { INTERNAL: SELF WALK, $callback, [$($stack)*], [$($out)* [$($inner)*]], $($rest)* }
+    };
+    (INTERNAL: SELF WALK, $callback:tt, [[{} [$($out:tt)*] [$($rest:tt)*]] $($stack:tt)*], [$($inner:tt)*],) => {
+        $crate::__guilty_rules! { INTERNAL: SELF WALK, $callback, [$($stack)*], [$($out)* {$($inner)*}], $($rest)* }
+    };
+    (INTERNAL: SELF WALK, [$($callback:tt)*], [], $out:tt,) => {
+        $crate::__guilty_rules! { INTERNAL: $($callback)* $out }
+    };
+    // impl-opt-consts-mod: the consts_mod option implements the consts for the trait in the given
+    //    hidden module (see trait-opt-consts-mod)
+    // calls on to split-impl, which separates the consts from the rest of the body (collecting
+    //    each in its own square brackets, as in def-trait)
+    (INTERNAL: IMPL OPTIONS, [consts_mod = $m:ident, $($opts:tt)*], $parts:tt, { $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: SPLIT IMPL, [$m [$($opts)*] $parts], [], [], { $($body)* });
+    };
+    // split-impl-per-variant: same as split-impl, keeping the tokens of the value (see
+    //    parse-impl-per-variant-const)
+    (INTERNAL: SPLIT IMPL, $state:tt, [$($consts:tt)*], $others:tt,
+     {
+         #[guilty(per_variant)] $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = match $selfname:ident $arms:tt;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SPLIT IMPL, $state,
+                        [$($consts)* #[guilty(per_variant)] $(#[$($cattr)*])* const $constname : $consttype
+                         = match $selfname $arms;],
+                        $others, { $($body)* });
+    };
+    // split-impl: move a const from the body to the consts
+    (INTERNAL: SPLIT IMPL, $state:tt, [$($consts:tt)*], $others:tt,
+     {
+         $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: SPLIT IMPL, $state,
+                        [$($consts)* $(#[$($cattr)*])* const $constname : $consttype = $constvalue;],
+                        $others, { $($body)* });
+    };
+    // split-impl-type: move an associated type to the consts as well (see split-trait-type)
+    (INTERNAL: SPLIT IMPL, $state:tt, $consts:tt, $others:tt,
+     {
+         $(#[$($tattr:tt)*])* type $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [SPLIT IMPL TYPE, $state, $consts, $others,], [],
+                        { $(#[$($tattr)*])* type $($body)* });
+    };
+    (INTERNAL: SPLIT IMPL TYPE, $state:tt, [$($consts:tt)*], $others:tt, [$($item:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SPLIT IMPL, $state, [$($consts)* $($item)*], $others, $body);
+    };
+    // split-impl-item: move any other item to the other items, see next-item
+    (INTERNAL: SPLIT IMPL, $state:tt, $consts:tt, [$($others:tt)*], {}) => {
+        $crate::__guilty_rules!(INTERNAL: SPLIT IMPL DONE, $state, $consts, { $($others)* });
+    };
+    (INTERNAL: SPLIT IMPL, $state:tt, $consts:tt, $others:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [SPLIT IMPL, $state, $consts, $others,], [], $body);
+    };
+    (INTERNAL: SPLIT IMPL, $state:tt, $consts:tt, [$($others:tt)*], [$($item:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: SPLIT IMPL, $state, $consts, [$($others)* $($item)*], $body);
+    };
+    // split-impl-done: output the impl of the trait in the consts module, then continue with the
+    //    options
+    (INTERNAL: SPLIT IMPL DONE, [$m:ident $opts:tt [[$($generics:tt)*] [$traitname:ident $($targs:tt)*] [$($structname:tt)*] [$($where:tt)*]]],
+     [$($consts:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: DEFINE IMPL,
+                        [parts [[$($generics)*] [$m::$traitname $($targs)*] [$($structname)*] [$($where)*]]
+                         #[__guilty_mangle $opts] impl<$($generics)*> $m::$traitname $($targs)* for $($structname)* $($where)*],
+                        { $($consts)* });
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, $opts, [[$($generics)*] [$traitname $($targs)*] [$($structname)*] [$($where)*]], $body);
+    };
+    // impl-opt-comma: skip a stray separator between options
+    (INTERNAL: IMPL OPTIONS, [, $($opts:tt)*], $parts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, [$($opts)*], $parts, $body);
+    };
+    // impl-opt-unsafe: all options are processed, go on to parse an unsafe impl (with the
+    //    attribute added by impl-opt-unmangled, if any, after the unsafe)
+    (INTERNAL: IMPL OPTIONS, [$(#[$($attr:tt)*])* unsafe, $(,)* $(#[$($mattr:tt)*])*],
+     [[$($generics:tt)*] [$($traitname:tt)*] [$($structname:tt)*] [$($where:tt)*]], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: DEFINE IMPL,
+                        [parts [[$($generics)*] [$($traitname)*] [$($structname)*] [$($where)*]]
+                         $(#[$($attr)*])* $(#[$($mattr)*])* unsafe impl<$($generics)*> $($traitname)* for $($structname)* $($where)*],
+                        $body);
+    };
+    // impl-opt-done: all options are processed, go on to parse the impl (with the attributes left
+    //    by item-attrs-done)
+    // the header of a trait impl starts with its parts, for parse-impl-impl-default
+    (INTERNAL: IMPL OPTIONS, [$(#[$($attr:tt)*])*], [[$($generics:tt)*] [] [$($structname:tt)*] [$($where:tt)*]], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: DEFINE IMPL, [inherent $(#[$($attr)*])* impl<$($generics)*> $($structname)* $($where)*], $body);
+    };
+    (INTERNAL: IMPL OPTIONS, [$(#[$($attr:tt)*])*], [[$($generics:tt)*] [$($traitname:tt)*] [$($structname:tt)*] [$($where:tt)*]], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: DEFINE IMPL,
+                        [parts [[$($generics)*] [$($traitname)*] [$($structname)*] [$($where)*]]
+                         $(#[$($attr)*])* impl<$($generics)*> $($traitname)* for $($structname)* $($where)*],
+                        $body);
+    };
+    // impl-opt-unmangled: see trait-opt-unmangled (the attribute goes at the end of the options,
+    //    with those left by item-attrs-done)
+    (INTERNAL: IMPL OPTIONS, [unmangled, $($opts:tt)*], $parts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL OPTIONS, [$($opts)* #[__guilty_mangle(unmangled)]], $parts, $body);
+    };
+    // impl-opt-error: an option which is not one of the above
+    (INTERNAL: IMPL OPTIONS, [$opt:tt $($opts:tt)*], $($rest:tt)*) => {
+        compile_error!(concat!("guilty!: unknown option `", stringify!($opt), "` for an impl (or wrong syntax for it)"));
+    };
+
+    // reflected: drop the consts marked no_reflect from a list collected by trait-consts or
+    //    impl-consts, then pass it on to the internal call given in the first square brackets
+    // reflected-fast: a list in which the consts have no attributes other than docs and cfgs
+    //    (in that order) is passed on at once
+    (INTERNAL: REFLECTED, [$($callback:tt)*], [],
+     [$([$(#[doc $($doc:tt)*])* $(#[cfg $pred:tt])*] $constname:ident : $consttype:ty $(= $constvalue:expr)*;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)*
+                        [$([$(#[doc $($doc)*])* $(#[cfg $pred])*] $constname : $consttype $(= $constvalue)*;)*]);
+    };
+    // reflected-fn-attrs: drop the fn_attr marker, whose attributes are only for the functions
+    //    generated for the consts themselves
+    (INTERNAL: REFLECTED, $callback:tt, $kept:tt,
+     [[#[guilty(fn_attr $fattrs:tt)] $($cattr:tt)*] $constname:ident : $consttype:ty $(= $constvalue:expr)*; $($rest:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: REFLECTED, $callback, $kept,
+                [[$($cattr)*] $constname : $consttype $(= $constvalue)*; $($rest)*]);
+    };
+    (INTERNAL: REFLECTED, $callback:tt, $kept:tt,
+     [[#[guilty(no_reflect)] $($cattr:tt)*] $constname:ident : $consttype:ty $(= $constvalue:expr)*; $($rest:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: REFLECTED, $callback, $kept, [$($rest)*]);
+    };
+    // reflected-typenum: drop the typenum marker, whose type is not listed
+    (INTERNAL: REFLECTED, $callback:tt, $kept:tt,
+     [[#[guilty(typenum = $tyname:ident)] $($cattr:tt)*] $constname:ident : $consttype:ty $(= $constvalue:expr)*; $($rest:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: REFLECTED, $callback, $kept,
+                [[$($cattr)*] $constname : $consttype $(= $constvalue)*; $($rest)*]);
+    };
+    // reflected-per-variant: the consts with the per_variant option are dropped as well, since
+    //    they have no value without an instance
+    (INTERNAL: REFLECTED, $callback:tt, $kept:tt,
+     [[#[guilty(per_variant)] $($cattr:tt)*] $constname:ident : $consttype:ty $(= $constvalue:expr)*; $($rest:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: REFLECTED, $callback, $kept, [$($rest)*]);
+    };
+    (INTERNAL: REFLECTED, $callback:tt, [$($kept:tt)*],
+     [$cattrs:tt $constname:ident : $consttype:ty $(= $constvalue:expr)*; $($rest:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: REFLECTED, $callback, [$($kept)* $cattrs $constname : $consttype $(= $constvalue)*;], [$($rest)*]);
+    };
+    (INTERNAL: REFLECTED, [$($callback:tt)*], $kept:tt, []) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* $kept);
+    };
+    // cfg-only: keep only the cfg attributes of the consts in a list collected by trait-consts or
+    //    impl-consts (see cfg-attrs), then pass it on as in reflected
+    (INTERNAL: CFG ONLY, [$($callback:tt)*], $kept:tt, []) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* $kept);
+    };
+    // cfg-only-fast: the same for a list in which the consts have no attributes other than docs
+    //    and cfgs (in that order)
+    (INTERNAL: CFG ONLY, [$($callback:tt)*], [],
+     [$([$(#[doc $($doc:tt)*])* $(#[cfg $pred:tt])*] $constname:ident : $consttype:ty $(= $constvalue:expr)*;)*]) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* [$([$(#[cfg $pred])*] $constname : $consttype $(= $constvalue)*;)*]);
+    };
+    // cfg-only-per-variant: drop the consts with the per_variant option (see reflected-per-variant)
+    (INTERNAL: CFG ONLY, $callback:tt, $kept:tt,
+     [[#[guilty(per_variant)] $($cattr:tt)*] $constname:ident : $consttype:ty $(= $constvalue:expr)*; $($rest:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ONLY, $callback, $kept, [$($rest)*]);
+    };
+    (INTERNAL: CFG ONLY, $callback:tt, $kept:tt,
+     [$cattrs:tt $constname:ident : $consttype:ty $(= $constvalue:expr)*; $($rest:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ATTRS,
+                        [CFG ONLY, $callback, $kept, [$constname : $consttype $(= $constvalue)*;], [$($rest)*],],
+                        [], $cattrs);
+    };
+    (INTERNAL: CFG ONLY, $callback:tt, [$($kept:tt)*], [$($constdef:tt)*], $rest:tt, $cfgs:tt) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ONLY, $callback, [$($kept)* $cfgs $($constdef)*], $rest);
+    };
+    // cfg-attrs: pick the cfg attributes out of the attributes of a const, so that the items
+    //    generated for it by the options are gated the same way, and pass them on (in square
+    //    brackets) to the internal call given in the first square brackets
+    (INTERNAL: CFG ATTRS, $callback:tt, [$($cfgs:tt)*], [#[cfg $pred:tt] $($cattr:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ATTRS, $callback, [$($cfgs)* #[cfg $pred]], [$($cattr)*]);
+    };
+    (INTERNAL: CFG ATTRS, $callback:tt, $cfgs:tt, [#[$($attr:tt)*] $($cattr:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ATTRS, $callback, $cfgs, [$($cattr)*]);
+    };
+    (INTERNAL: CFG ATTRS, [$($callback:tt)*], $cfgs:tt, []) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* $cfgs);
+    };
+
+    // impl-consts: collect the consts of an impl, without outputting anything
+    // at the end of the body, the list is passed on to the internal call given in the first
+    //    square brackets, as [[attrs] NAME: Type = value; ...]
+    // impl-consts-fast: a body of nothing but consts is collected at once
+    (INTERNAL: IMPL CONSTS, [$($callback:tt)*], [],
+     {
+         $($(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = $constvalue:expr;)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* [$([$(#[$($cattr)*])*] $constname : $consttype = $constvalue;)*]);
+    };
+    // impl-consts-fast-fns: the same for consts followed by simple methods (as in parse-impl-fn,
+    //    but without attributes, which could not be told apart from those of a const)
+    (INTERNAL: IMPL CONSTS, [$($callback:tt)*], [],
+     {
+         $($(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = $constvalue:expr;)*
+         $(fn $fname:ident $args:tt $(-> $ret:ty)* { $($fbody:tt)* })+
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* [$([$(#[$($cattr)*])*] $constname : $consttype = $constvalue;)*]);
+    };
+    (INTERNAL: IMPL CONSTS, [$($callback:tt)*], [$($consts:tt)*],
+     {
+         $(#[$($cattr:tt)*])* const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL CONSTS, [$($callback)*],
+                [$($consts)* [$(#[$($cattr)*])*] $constname : $consttype = $constvalue;],
+                { $($body)* });
+    };
+    (INTERNAL: IMPL CONSTS, [$($callback:tt)*], [$($consts:tt)*], {}) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* [$($consts)*]);
+    };
+    // impl-consts-default: a default const is collected like any other
+    (INTERNAL: IMPL CONSTS, $callback:tt, $consts:tt,
+     {
+         $(#[$($cattr:tt)*])* default const $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL CONSTS, $callback, $consts, { $(#[$($cattr)*])* const $($body)* });
+    };
+    // skip any other item, see next-item
+    (INTERNAL: IMPL CONSTS, $callback:tt, $consts:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [IMPL CONSTS, $callback, $consts,], [], $body);
+    };
+    (INTERNAL: IMPL CONSTS, $callback:tt, $consts:tt, $item:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL CONSTS, $callback, $consts, $body);
+    };
+
+    // def-impl: start going through the items of an impl, one at a time (see def-trait)
+    (INTERNAL: DEFINE IMPL, $header:tt, { $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header, [], { $($body)* });
+    };
+    // parse-impl-fn-attrs: same as parse-trait-fn-attrs (before the visibility)
+    (INTERNAL: IMPL BODY, $header:tt, $done:tt,
+     {
+         #[guilty(fn_attr($($fattr:meta),*))] $(#[$($cattr:tt)*])* $vis:vis const $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header, $done,
+                { $(#[$($cattr)*])* $(#[$fattr])* $vis const $($body)* });
+    };
+    // parse-impl-noreflect: drop the no_reflect marker from a const (see reflected)
+    (INTERNAL: IMPL BODY, $header:tt, $done:tt,
+     {
+         #[guilty(no_reflect)] $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header, $done, { $($body)* });
+    };
+    // parse-impl-impl-default: output an impl of Default for the type which returns the value of
+    //    the const (gated like it, see cfg-attrs), then drop the impl_default marker
+    (INTERNAL: IMPL BODY, [parts $parts:tt $($header:tt)*], $done:tt,
+     {
+         #[guilty(impl_default)] $(#[$($cattr:tt)*])* $vis:vis const $constname:ident $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ATTRS, [IMPL DEFAULT, [$($header)*] $parts $constname,], [], [$(#[$($cattr)*])*]);
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, [parts $parts $($header)*], $done,
+                { $(#[$($cattr)*])* $vis const $constname $($body)* });
+    };
+    (INTERNAL: IMPL BODY, [inherent $($header:tt)*], $done:tt,
+     {
+         #[guilty(impl_default)] $(#[$($cattr:tt)*])* $vis:vis const $constname:ident $($body:tt)*
+     }) => {
+        compile_error!(concat!("guilty!: the impl_default option of const `", stringify!($constname),
+                               "` needs an impl of a trait"));
+    };
+    // the other impls of the trait made by the options (as for &Struct) don't get one
+    (INTERNAL: IMPL BODY, $header:tt, $done:tt,
+     {
+         #[guilty(impl_default)] $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header, $done, { $($body)* });
+    };
+    // the header of the impl is the mode of item-redir for the name of the function
+    (INTERNAL: IMPL DEFAULT, $header:tt [[$($generics:tt)*] [$($traitname:tt)*] [$($structname:tt)*] [$($where:tt)*]] $constname:ident,
+     [$($cfgs:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: AS ITEM, $header
+                $($cfgs)*
+                impl<$($generics)*> $crate::__core::default::Default for $($structname)* $($where)* {
+                    fn default() -> Self {
+                        <$($structname)* as $($traitname)*>::__guilty_mangle $constname()
+                    }
+                });
+    };
+    // parse-impl-env-const: parse a const with the env option (the value is looked up in the
+    //    environment variable once, and cached)
+    (INTERNAL: IMPL BODY, $header:tt, [$($done:tt)*],
+     {
+         #[guilty(env = $var:expr)] $(#[$cattr:meta])* $vis:vis const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] $vis fn __guilty_mangle $constname() -> $consttype {
+                        static CACHE: $crate::__EnvCache<$consttype> = $crate::__EnvCache::new();
+                        match $crate::__env_get(&CACHE, $var) {
+                            $crate::__core::option::Option::Some(value) => value,
+                            $crate::__core::option::Option::None => $constvalue,
+                        }
+                    }
+                ],
+                { $($body)* });
+    };
+    // parse-impl-static-const: parse a const with the static option (the value is stored in a
+    //    static, so that every access returns the same one)
+    (INTERNAL: IMPL BODY, $header:tt, [$($done:tt)*],
+     {
+         #[guilty(static)] $(#[$cattr:meta])* $vis:vis const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] $vis fn __guilty_mangle $constname() -> $consttype {
+                        static VALUE: $consttype = $constvalue;
+                        VALUE
+                    }
+                ],
+                { $($body)* });
+    };
+    // parse-impl-cached-const: parse a const with the cached option (the value is computed on
+    //    first access, and the function returns a reference to it)
+    //    the static is shared by all the instantiations of a generic impl, so the value is computed
+    //    by a nested fn rather than a closure: like the static, it can't use the generic parameters
+    //    of the impl (or Self), so a value which depends on them is an error, not shared
+    (INTERNAL: IMPL BODY, $header:tt, [$($done:tt)*],
+     {
+         #[guilty(cached)] $(#[$cattr:meta])* $vis:vis const $constname:ident : & 'static $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] $vis fn __guilty_mangle $constname() -> &'static $consttype {
+                        fn __guilty_init() -> $consttype { $constvalue }
+                        static CACHE: $crate::__Cached<$consttype> = $crate::__Cached::new();
+                        CACHE.get(__guilty_init)
+                    }
+                ],
+                { $($body)* });
+    };
+    (INTERNAL: IMPL BODY, $header:tt, $done:tt,
+     {
+         #[guilty(cached)] $(#[$cattr:meta])* $vis:vis const $constname:ident : $($body:tt)*
+     }) => {
+        compile_error!(concat!("guilty!: cached const `", stringify!($constname), "` must have a `&'static` type"));
+    };
+    // parse-impl-typenum: output the associated type named by the typenum option of a const, the
+    //    typenum type for its value (see __guilty_typenum; a literal fragment could not be matched
+    //    there, so the value is taken as a token tree), then drop the marker
+    (INTERNAL: IMPL BODY, $header:tt, $done:tt,
+     {
+         #[guilty(typenum = $tyname:ident)] $(#[$($cattr:tt)*])* $vis:vis const $constname:ident : $consttype:ty
+         = $constvalue:tt;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: CFG ATTRS,
+                        [TYPENUM TYPE, [IMPL BODY, $header,], $done,
+                         [type $tyname = $crate::__guilty_typenum!($constvalue);],
+                         { $(#[$($cattr)*])* $vis const $constname : $consttype = $constvalue; $($body)* },],
+                        [], [$(#[$($cattr)*])*]);
+    };
+    (INTERNAL: IMPL BODY, $header:tt, $done:tt,
+     {
+         #[guilty(typenum = $tyname:ident)] $(#[$($cattr:tt)*])* $vis:vis const $constname:ident : $($body:tt)*
+     }) => {
+        compile_error!(concat!("guilty!: the typenum option of const `", stringify!($constname),
+                               "` needs an integer literal value"));
+    };
+    // parse-impl-per-variant-const: parse a const with the per_variant option, whose value is a
+    //    `match self { ... }`, into a method taking &self (never a const fn)
+    // the self of the method has to be the one in the value, which is not the same as a self
+    //    written here (macro_rules hygiene), so the value is matched token by token to get it
+    (INTERNAL: IMPL BODY, $header:tt, [$($done:tt)*],
+     {
+         #[guilty(per_variant)] $(#[$cattr:meta])* $vis:vis const $constname:ident : $consttype:ty
+         = match $selfname:ident $arms:tt;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] $vis fn __guilty_mangle $constname(&$selfname) -> $consttype {
+                        match $selfname $arms
+                    }
+                ],
+                { $($body)* });
+    };
+    (INTERNAL: IMPL BODY, $header:tt, $done:tt,
+     {
+         #[guilty(per_variant)] $(#[$cattr:meta])* $vis:vis const $constname:ident : $($body:tt)*
+     }) => {
+        compile_error!(concat!("guilty!: the value of per_variant const `", stringify!($constname),
+                               "` must be `match self { ... }`"));
+    };
+    // parse-inherent-litconst, parse-inherent-const: same as parse-impl-litconst and
+    //    parse-impl-const, in an inherent impl (whose header starts with an inherent marker), where
+    //    the functions can be const fns (see __guilty_inherent_fn)
+    (INTERNAL: IMPL BODY, [inherent $($header:tt)*], [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* $vis:vis const $constname:ident : $consttype:ty = $constvalue:literal;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, [inherent $($header)*],
+                [
+                    $($done)*
+                    $crate::__guilty_inherent_fn! {
+                        [$(#[$cattr])* #[doc = ""] #[doc = concat!("Value: `", stringify!($constvalue), "`")]]
+                        $vis __guilty_mangle $constname : $consttype = $constvalue
+                    }
+                ],
+                { $($body)* });
+    };
+    (INTERNAL: IMPL BODY, [inherent $($header:tt)*], [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* $vis:vis const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, [inherent $($header)*],
+                [
+                    $($done)*
+                    $crate::__guilty_inherent_fn! { [$(#[$cattr])*] $vis __guilty_mangle $constname : $consttype = $constvalue }
+                ],
+                { $($body)* });
+    };
+    // parse-impl-default-const: same as parse-impl-litconst and parse-impl-const, for a const
+    //    marked default (with the specialization feature), which becomes a default fn
+    (INTERNAL: IMPL BODY, $header:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* default const $constname:ident : $consttype:ty = $constvalue:literal;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[doc = ""] #[doc = concat!("Value: `", stringify!($constvalue), "`")]
+                    #[allow(non_snake_case)] default fn __guilty_mangle $constname() -> $consttype { $constvalue }
+                ],
+                { $($body)* });
+    };
+    (INTERNAL: IMPL BODY, $header:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* default const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] default fn __guilty_mangle $constname() -> $consttype { $constvalue }
+                ],
+                { $($body)* });
+    };
+    // parse-impl-litconst: same as parse-impl-const, for a value which is a literal (which is added
+    //    to the docs)
+    (INTERNAL: IMPL BODY, $header:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* $vis:vis const $constname:ident : $consttype:ty = $constvalue:literal;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[doc = ""] #[doc = concat!("Value: `", stringify!($constvalue), "`")]
+                    #[allow(non_snake_case)] $vis fn __guilty_mangle $constname() -> $consttype { $constvalue }
+                ],
+                { $($body)* });
+    };
+    // parse-impl-const: parse a const (with a visibility, in an inherent impl)
+    // the square brackets contain the header of the impl, e.g. [impl Trait for Struct]
+    (INTERNAL: IMPL BODY, $header:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* $vis:vis const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] $vis fn __guilty_mangle $constname() -> $consttype { $constvalue }
+                ],
+                { $($body)* });
+    };
+    // parse-impl-static: parse a static into a function returning a reference to it, stored in a
+    //    static inside the function
+    (INTERNAL: IMPL BODY, $header:tt, [$($done:tt)*],
+     {
+         $(#[$cattr:meta])* $vis:vis static $staticname:ident : $statictype:ty = $staticvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header,
+                [
+                    $($done)*
+                    $(#[$cattr])* #[allow(non_snake_case)] $vis fn __guilty_mangle $staticname() -> &'static $statictype {
+                        static VALUE: $statictype = $staticvalue;
+                        &VALUE
+                    }
+                ],
+                { $($body)* });
+    };
+    // parse-impl-fn: fast path for a simple method, which is passed through (anything fancier goes
+    //    through parse-impl-item)
+    (INTERNAL: IMPL BODY, $header:tt, [$($done:tt)*],
+     {
+         $(#[$fattr:meta])* fn $fname:ident $args:tt $(-> $ret:ty)* { $($fbody:tt)* }
+         $($body:tt)*
+     }) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header,
+                [
+                    $($done)*
+                    $(#[$fattr])* fn $fname $args $(-> $ret)* { $($fbody)* }
+                ],
+                { $($body)* });
+    };
+    // def-impl-done: all items are seen, output the impl
+    // indirection through __guilty_mangle, as in def-trait-done
+    (INTERNAL: IMPL BODY, [inherent $($header:tt)*], [$($done:tt)*], {}) => {
+        $crate::__guilty_mangle! { [] $($header)* { $($done)* } }
+    };
+    (INTERNAL: IMPL BODY, [parts $parts:tt $($header:tt)*], [$($done:tt)*], {}) => {
+        $crate::__guilty_mangle! { [] $($header)* { $($done)* } }
+    };
+    (INTERNAL: IMPL BODY, [$($header:tt)*], [$($done:tt)*], {}) => {
+        $crate::__guilty_mangle! { [] $($header)* { $($done)* } }
+    };
+    // parse-impl-nodefconst: a const (or a static) without a value can only be declared in a trait
+    (INTERNAL: IMPL BODY, $header:tt, $done:tt,
+     {
+         $(#[$cattr:meta])* $vis:vis const $constname:ident : $consttype:ty;
+         $($body:tt)*
+     }) => {
+        compile_error!(concat!("guilty!: const `", stringify!($constname), "` needs a value in an impl"));
+    };
+    (INTERNAL: IMPL BODY, $header:tt, $done:tt,
+     {
+         $(#[$cattr:meta])* $vis:vis static $staticname:ident : $statictype:ty;
+         $($body:tt)*
+     }) => {
+        compile_error!(concat!("guilty!: static `", stringify!($staticname), "` needs a value in an impl"));
+    };
+    // parse-impl-item: any other item is passed through, see next-item
+    (INTERNAL: IMPL BODY, $header:tt, $done:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, [IMPL BODY, $header, $done,], [], $body);
+    };
+    (INTERNAL: IMPL BODY, $header:tt, [$($done:tt)*], [$($item:tt)*], $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: IMPL BODY, $header, [$($done)* $($item)*], $body);
+    };
+
+    // next-item: split the first item (other than a const) off the body, one token tree at a time,
+    //    then pass it on to the internal call given in the first square brackets, as
+    //    [item], { rest of the body }
+    // an item ends at a semicolon or a braced block (reaching the end of the body means that the
+    //    item is missing its end)
+    (INTERNAL: NEXT ITEM, [$($callback:tt)*], [$($item:tt)*], { ; $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* [$($item)* ;], { $($body)* });
+    };
+    (INTERNAL: NEXT ITEM, [$($callback:tt)*], [$($item:tt)*], { { $($block:tt)* } $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: $($callback)* [$($item)* { $($block)* }], { $($body)* });
+    };
+    (INTERNAL: NEXT ITEM, $callback:tt, [$($item:tt)*], { $next:tt $($body:tt)* }) => {
+        $crate::__guilty_rules!(INTERNAL: NEXT ITEM, $callback, [$($item)* $next], { $($body)* });
+    };
+    (INTERNAL: NEXT ITEM, $callback:tt, [$($item:tt)*], {}) => {
+        compile_error!(concat!("guilty!: expected `;` or a body after `", stringify!($($item)*), "`"));
+    };
+
+    // array-impl-where: move a token of the where clause (if any) into the square brackets
+    (INTERNAL: ARRAY IMPL, $info:tt, $where:tt, { $($body:tt)* } $next:ident $($rest:tt)*) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [ARRAY IMPL, $info, $where, { $($body)* }], $next $($rest)*);
+    };
+    (INTERNAL: ARRAY IMPL, $info:tt, $where:tt, { $($body:tt)* } # $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: MORE ITEMS, [ARRAY IMPL, $info, $where, { $($body)* }], # $($rest)+);
+    };
+    (INTERNAL: ARRAY IMPL, $info:tt, [$($where:tt)*], $next:tt $($rest:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ARRAY IMPL, $info, [$($where)* $next], $($rest)+);
+    };
+    // array-impl-lengths: only the body is left, so stamp out the impls
+    (INTERNAL: ARRAY IMPL, $info:tt, $where:tt, $body:tt) => {
+        $crate::__guilty_rules!(INTERNAL: ARRAY LENGTHS, $info $where $body,
+                        [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32]);
+    };
+    // array-lengths: output the impl for one length, via parse-impl-const
+    (INTERNAL: ARRAY LENGTHS, [$traitname:ident $elem:ident] [$($where:tt)*] { $($body:tt)* }, [$n:tt $($ns:tt)*]) => {
+        $crate::__guilty_rules!(INTERNAL: DEFINE IMPL, [impl<$elem> $traitname for [$elem; $n] $($where)*],
+                        {
+                            const LEN: usize = $n;
+                            $($body)*
+                        });
+        $crate::__guilty_rules!(INTERNAL: ARRAY LENGTHS, [$traitname $elem] [$($where)*] { $($body)* }, [$($ns)*]);
+    };
+    (INTERNAL: ARRAY LENGTHS, $info:tt $where:tt $body:tt, []) => {};
+
+    // access-path: move the path to the type into the square brackets, until only the const is left
+    (INTERNAL: ACCESS PATH, [$($structname:tt)*], :: $constname:ident) => {
+        $crate::__guilty_rules!(INTERNAL: ACCESS CONST, ($($structname)*), $constname)
+    };
+    (INTERNAL: ACCESS PATH, [$($structname:tt)*], $next:tt $($path:tt)+) => {
+        $crate::__guilty_rules!(INTERNAL: ACCESS PATH, [$($structname)* $next], $($path)+)
+    };
+
+    // access: access a const defined with this macro
+    // For now, it just calls the function, since we turn consts into functions. In the future, it
+    // might do something more clever if the implementation changes.
+    // The name of the function is mangled (see item-redir).
+    (INTERNAL: ACCESS CONST, ($($structname:tt)*), $constname:ident) => {{
+        $crate::__guilty_mangle!([] $($structname)* :: __guilty_mangle $constname ())
+    }};
+
+    // check-overridden: look for the const in the list of consts defined by the impl
+    (INTERNAL: CHECK OVERRIDDEN, ($($structname:tt)*), $constname:ident) => {{
+        $($structname)* :: __guilty_overridden().contains(&stringify!($constname))
+    }};
+
+    // locate-const: look up the const in the locations recorded by the impl and the trait
+    (INTERNAL: LOCATE CONST, ($($structname:tt)*), $constname:ident) => {{
+        $($structname)* :: __guilty_location(stringify!($constname))
+    }};
+
+    // override-const: install the override, using the accessor to pin down the type of the value
+    (INTERNAL: OVERRIDE CONST, $structname:ty, ($($accessor:tt)*), $constname:ident, $value:expr) => {
+        $crate::ConstOverride::new::<$structname, _>($crate::__guilty_mangle!([] $($accessor)* :: __guilty_mangle $constname),
+                                                     stringify!($constname), $value)
+    };
+
+    // item-redir: Item redirection.
+    // For some reason the parser sometimes complains "expected item" when you are trying to output
+    // a perfectly good item. The solution (sometimes) is to redirect through a macro like this.
+    // The macro is __guilty_mangle, which turns each `__guilty_mangle NAME` in the item into
+    // the name of the function generated for the const NAME: __guilty_const_NAME, or NAME if the
+    // mode in the square brackets (the options or the header, for the extra items made by them),
+    // or a #[__guilty_mangle(...)] attribute on the item, mentions unmangled (see
+    // trait-opt-unmangled).
+    // A value which is parsed again as an expression can't have the markers in it, so they go
+    // in a call of __guilty_mangle of its own, whose mode is itself a marker: item-redir
+    // rewrites it along with the names, after which that call has nothing left to do.
+    (INTERNAL: AS ITEM, [$($mode:tt)*] $($item:tt)*) => {
+        $crate::__guilty_mangle! { [$($mode)*] $($item)* }
+    };
+    (INTERNAL: AS ITEM, $($item:tt)*) => {
+        $crate::__guilty_mangle! { [] $($item)* }
+    };
+}
+
+// The stand-in for the engine with the real-consts feature: traits and impls are passed through
+// with real associated consts, and const accesses are plain paths.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __guilty_real {
+    // an extension trait is a trait with the other trait as its supertrait
+    (#[guilty(blanket)] $(#[$attr:meta])* $vis:vis extend trait $foreign:path as $traitname:ident { $($body:tt)* }) => {
+        $(#[$attr])* $vis trait $traitname: $foreign { $($body)* }
+        impl<__T: ?Sized + $foreign> $traitname for __T {}
+    };
+    ($(#[$attr:meta])* $vis:vis extend trait $foreign:path as $traitname:ident { $($body:tt)* }) => {
+        $(#[$attr])* $vis trait $traitname: $foreign { $($body)* }
+    };
+
+    // real consts are not functions, so there is nothing to mangle
+    (#[guilty(unmangled)] $($item:tt)+) => { $($item)+ };
+    (unmangled $($path:tt)+) => { $($path)+ };
+
+    // the guilty options and the other special forms rely on the consts being functions
+    (#[guilty($($opt:tt)*)] $($item:tt)+) => {
+        compile_error!("guilty options are not available with the real-consts feature");
+    };
+    (impl $traitname:ident for [$elem:ident; 0..=32] $($rest:tt)+) => {
+        compile_error!("array impls are not available with the real-consts feature");
+    };
+    (overridden $($rest:tt)+) => {
+        compile_error!("guilty!(overridden ...) is not available with the real-consts feature")
+    };
+    (assert_overridden $($rest:tt)+) => {
+        compile_error!("guilty!(assert_overridden ...) is not available with the real-consts feature")
+    };
+    (override $($rest:tt)+) => {
+        compile_error!("guilty!(override ...) is not available with the real-consts feature")
+    };
+    (location $($rest:tt)+) => {
+        compile_error!("guilty!(location ...) is not available with the real-consts feature")
+    };
+
+    // access a const, with the type and const name as separate fragments
+    (const $structname:ty, $constname:ident) => {
+        <$structname>::$constname
+    };
+    (const $structname:ty as $traitname:path, $constname:ident) => {
+        <$structname as $traitname>::$constname
+    };
+
+    // implement a trait for several types
+    ($(#[$attr:meta])* impl $($traitname:ident)::+ for $first:ty, $($rest:ty),+ { $($body:tt)* }) => {
+        $(#[$attr])* impl $($traitname)::+ for $first { $($body)* }
+        $crate::__guilty_real! { $(#[$attr])* impl $($traitname)::+ for $($rest),+ { $($body)* } }
+    };
+
+    // define or implement a trait: anything that starts like an item
+    (# $($item:tt)+) => { # $($item)+ };
+    (pub $($item:tt)+) => { pub $($item)+ };
+    (unsafe $($item:tt)+) => { unsafe $($item)+ };
+    (trait $($item:tt)+) => { trait $($item)+ };
+    (impl $($item:tt)+) => { impl $($item)+ };
+
+    // access a const
+    ($($path:tt)+) => {
+        $($path)+
+    };
+}
+
+/// Macro for declaring/implementing traits with fake associated consts, with a stricter grammar
+///
+/// This is the next generation of `guilty!`, which stays as it is for backwards compatibility.
+/// Compared to `guilty!`:
+///
+/// - the item header is passed through untouched, so it may have generics, where clauses,
+///   supertraits, paths, `unsafe`, etc.
+/// - consts may appear anywhere in the trait/impl, mixed with the other items
+/// - consts are always written `const NAME: Type;` or `const NAME: Type = value;`, and nothing
+///   else: there is a single separator, `;`, and it is mandatory
+/// - there are no guilty options or other special forms
+///
+/// Consts are accessed with `guilty2!(Type::CONST)` or `guilty2!(<Type as Trait>::CONST)`, again
+/// with any generics or paths. As with `guilty!`, the names of the generated functions are
+/// mangled, unless the item starts with `#[guilty(unmangled)]`.
+#[macro_export]
+macro_rules! guilty2 {
+    // These are the user facing invocations. The const access arm, which matches anything, comes
+    // last because it would also match the internal calls.
+
+    // 1. define or implement a trait: anything that starts like an item
+    // the unmangled attribute is the only one guilty2! looks at (see trait-opt-unmangled)
+    (#[guilty(unmangled)] $($item:tt)+) => {
+        $crate::guilty2!(INTERNAL: HEADER, [#[__guilty_mangle(unmangled)]], $($item)+);
+    };
+    (# $($item:tt)+) => {
+        $crate::guilty2!(INTERNAL: HEADER, [], # $($item)+);
+    };
+    (pub $($item:tt)+) => {
+        $crate::guilty2!(INTERNAL: HEADER, [], pub $($item)+);
+    };
+    (unsafe $($item:tt)+) => {
+        $crate::guilty2!(INTERNAL: HEADER, [], unsafe $($item)+);
+    };
+    (trait $($item:tt)+) => {
+        $crate::guilty2!(INTERNAL: HEADER, [], trait $($item)+);
+    };
+    (impl $($item:tt)+) => {
+        $crate::guilty2!(INTERNAL: HEADER, [], impl $($item)+);
+    };
+
+    // Following are the internal macro calls. As in guilty!, they start with the tokens
+    // `INTERNAL:`. The header of the item is moved into the first square brackets one token tree at
+    // a time, until only the body remains. Then the items in the body are parsed one at a time:
+    // consts are turned into static functions which are collected in the third square brackets,
+    // while the other items collect in the second square brackets and are output first.
+
+    // header-done: only the body is left
+    (INTERNAL: HEADER, [$($header:tt)*], { $($body:tt)* }) => {
+        $crate::guilty2!(INTERNAL: BODY, [$($header)*], [], [], { $($body)* });
+    };
+    // header-tt: move a token tree into the header
+    (INTERNAL: HEADER, [$($header:tt)*], $next:tt $($item:tt)+) => {
+        $crate::guilty2!(INTERNAL: HEADER, [$($header)* $next], $($item)+);
+    };
+
+    // body-const: a const with a value
+    (INTERNAL: BODY, $header:tt, $items:tt, [$($consts:tt)*],
+     {
+         $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constvalue:expr;
+         $($body:tt)*
+     }) => {
+        $crate::guilty2!(INTERNAL: BODY, $header, $items,
+                 [$($consts)* $(#[$cattr])* #[allow(non_snake_case)] fn __guilty_mangle $constname() -> $consttype { $constvalue }],
+                 { $($body)* });
+    };
+    // body-const-decl: a const without a value
+    (INTERNAL: BODY, $header:tt, $items:tt, [$($consts:tt)*],
+     {
+         $(#[$cattr:meta])* const $constname:ident : $consttype:ty;
+         $($body:tt)*
+     }) => {
+        $crate::guilty2!(INTERNAL: BODY, $header, $items,
+                 [$($consts)* $(#[$cattr])* #[allow(non_snake_case)] fn __guilty_mangle $constname() -> $consttype;],
+                 { $($body)* });
+    };
+    // body-fn: fast path for a simple method (anything fancier goes through body-item)
+    (INTERNAL: BODY, $header:tt, [$($items:tt)*], $consts:tt,
+     {
+         $(#[$fattr:meta])* fn $fnname:ident $args:tt $(-> $ret:ty)* { $($fnbody:tt)* }
+         $($body:tt)*
+     }) => {
+        $crate::guilty2!(INTERNAL: BODY, $header, [$($items)* $(#[$fattr])* fn $fnname $args $(-> $ret)* { $($fnbody)* }],
+                 $consts, { $($body)* });
+    };
+    // body-done: output the item, with the names of the functions mangled as in guilty!
+    (INTERNAL: BODY, [$($header:tt)*], [$($items:tt)*], [$($consts:tt)*], {}) => {
+        $crate::__guilty_mangle! { [] $($header)* { $($items)* $($consts)* } }
+    };
+    // body-item: any other item, which is passed through
+    (INTERNAL: BODY, $header:tt, $items:tt, $consts:tt, { $($body:tt)+ }) => {
+        $crate::guilty2!(INTERNAL: ITEM, $header, $items, $consts, [], { $($body)+ });
+    };
+
+    // item-end: an item ends at a semicolon or a braced block
+    (INTERNAL: ITEM, $header:tt, [$($items:tt)*], $consts:tt, [$($item:tt)*], { ; $($body:tt)* }) => {
+        $crate::guilty2!(INTERNAL: BODY, $header, [$($items)* $($item)* ;], $consts, { $($body)* });
+    };
+    (INTERNAL: ITEM, $header:tt, [$($items:tt)*], $consts:tt, [$($item:tt)*], { { $($block:tt)* } $($body:tt)* }) => {
+        $crate::guilty2!(INTERNAL: BODY, $header, [$($items)* $($item)* { $($block)* }], $consts, { $($body)* });
+    };
+    // item-tt: move a token tree into the item
+    (INTERNAL: ITEM, $header:tt, $items:tt, $consts:tt, [$($item:tt)*], { $next:tt $($body:tt)* }) => {
+        $crate::guilty2!(INTERNAL: ITEM, $header, $items, $consts, [$($item)* $next], { $($body)* });
+    };
+
+    // 2. access a const declared with this macro (or guilty!), the same way as guilty!
+    ($($path:tt)+) => {
+        $crate::__guilty_rules!($($path)+)
+    };
+}
+
+/// Accesses a const if a concrete (non-generic) type implements the trait, or evaluates to a
+/// fallback value otherwise
+///
+/// Inside a generic function, `<T as Trait>::CONST` always evaluates to the fallback, even when
+/// `T` implements the trait, unless the function has a `T: Trait` bound (and then the macro is not
+/// needed). The const type can't mention the function's generic parameters either. It is meant
+/// for places where the type is written out, such as macros that are expanded for each element
+/// type.
+///
+/// `guilty_try_const!(<Struct as Trait>::CONST: Type else fallback)` is the const if
+/// `Struct: Trait`, and `fallback` if not. The type of the const and the trait have to be spelled
+/// out (so there is no `Struct::CONST else fallback` form), since there is no way to get at them
+/// when the trait is not implemented.
+///
+/// This uses autoref-based dispatch rather than specialization, so the choice is made where the
+/// macro is called, with the bounds known there, which is why generic code gets the fallback.
+#[macro_export]
+macro_rules! guilty_try_const {
+    // the type is collected up to the `else`, which can't follow a `ty` fragment
+    (<$structname:ty as $traitname:path>::$constname:ident: $($rest:tt)+) => {
+        $crate::guilty_try_const!(INTERNAL: TYPE, $structname, $traitname, $constname, [], $($rest)+)
+    };
+
+    (INTERNAL: TYPE, $structname:ty, $traitname:path, $constname:ident, [$($t:tt)+],
+     else $fallback:expr) => {
+        $crate::guilty_try_const!(INTERNAL: DONE, $structname, $traitname, $constname, $($t)+,
+                                  $fallback)
+    };
+
+    (INTERNAL: TYPE, $structname:ty, $traitname:path, $constname:ident, [$($t:tt)*],
+     $next:tt $($rest:tt)+) => {
+        $crate::guilty_try_const!(INTERNAL: TYPE, $structname, $traitname, $constname,
+                                  [$($t)* $next], $($rest)+)
+    };
+
+    (INTERNAL: DONE, $structname:ty, $traitname:path, $constname:ident, $t:ty,
+     $fallback:expr) => {{
+        struct __Probe<S: ?Sized>($crate::__core::marker::PhantomData<S>);
+        // found (tried first): the method takes &__Probe, which matches without autoref
+        trait __Found {
+            fn __try_const(&self, _fallback: $t) -> $t;
+        }
+        impl<S: ?Sized + $traitname> __Found for __Probe<S> {
+            fn __try_const(&self, _fallback: $t) -> $t {
+                $crate::guilty!(<S as $traitname>::$constname)
+            }
+        }
+        // fallback: the method takes &&__Probe, which matches for any type
+        trait __Fallback {
+            fn __try_const(&self, fallback: $t) -> $t {
+                fallback
+            }
+        }
+        impl<'a, S: ?Sized> __Fallback for &'a __Probe<S> {}
+
+        (&__Probe::<$structname>($crate::__core::marker::PhantomData)).__try_const($fallback)
+    }};
+}
+
+/// Defines fake consts on a type as aliases of existing ones
+///
+#[cfg_attr(feature = "real-consts", doc = "```ignore")]
+#[cfg_attr(not(feature = "real-consts"), doc = "```")]
+/// # #[macro_use] extern crate guilt_by_association;
+/// # guilty! { trait Trait { const OLD_NAME: Type; } }
+/// # type Type = u32;
+/// # struct Struct;
+/// # guilty! { impl Trait for Struct { const OLD_NAME: Type = 7; } }
+/// guilty_alias! {
+///     impl Struct {
+///         pub const NEW_NAME: Type = <Struct as Trait>::OLD_NAME;
+///     }
+/// }
+/// # fn main() {
+/// # assert_eq!(guilty!(Struct::NEW_NAME), 7);
+/// # }
+/// ```
+///
+/// generates an inherent function for `NEW_NAME`, accessible as `guilty!(Struct::NEW_NAME)`,
+/// which forwards to the const on the right. This is meant for renaming consts: the original may be
+/// marked `#[deprecated]` without the alias triggering the warning.
+#[macro_export]
+macro_rules! guilty_alias {
+    (impl $structname:ty { $($(#[$attr:meta])* $vis:vis const $name:ident: $t:ty = $target:expr;)* }) => {
+        $crate::__guilty_mangle! {
+            []
+            impl $structname {
+                $(
+                    $(#[$attr])*
+                    #[allow(non_snake_case)]
+                    $vis fn __guilty_mangle $name() -> $t {
+                        #[allow(deprecated)]
+                        { __guilty_mangle $target() }
+                    }
+                )*
+            }
+        }
+    };
+}
+
+/// Defines a trait with the union of the consts of other traits, implemented for every type which
+/// implements them all
+///
+#[cfg_attr(feature = "real-consts", doc = "```ignore")]
+#[cfg_attr(not(feature = "real-consts"), doc = "```")]
+/// # #[macro_use] extern crate guilt_by_association;
+/// # guilty! { pub trait Config { const LIMIT: u32; const NAME: &'static str; } }
+/// # guilty! { pub trait Meta { const VERSION: u32; } }
+/// guilty_compose! {
+///     pub trait Settings {
+///         use Config { const LIMIT: u32; const NAME: &'static str; }
+///         use Meta { const VERSION: u32; }
+///     }
+/// }
+/// # struct Struct;
+/// # guilty! { impl Config for Struct { const LIMIT: u32 = 8; const NAME: &'static str = "s"; } }
+/// # guilty! { impl Meta for Struct { const VERSION: u32 = 2; } }
+/// # fn limit<T: Settings>() -> u32 { guilty!(T::LIMIT) }
+/// # fn main() {
+/// # assert_eq!(limit::<Struct>(), 8);
+/// # assert_eq!(guilty!(<Struct as Settings>::VERSION), 2);
+/// # }
+/// ```
+///
+/// generates the trait `Settings`, with forwarding versions of the listed consts, and a blanket
+/// impl for `T: Config + Meta`, so that generic code can take `T: Settings` and access
+/// `guilty!(T::LIMIT)`, etc. (The consts have to be listed since the macro can't see the traits'
+/// definitions.) Since the composed trait is not a supertrait of the others, types which
+/// implement them all have two of each const, so access them through the trait you mean, such
+/// as `guilty!(<Struct as Settings>::LIMIT)`, when several are in scope.
+#[macro_export]
+macro_rules! guilty_compose {
+    ($(#[$attr:meta])* $vis:vis trait $name:ident {
+        $(use $traitname:path { $($(#[$constattr:meta])* const $constname:ident: $t:ty;)* })+
+    }) => {
+        $crate::__guilty_mangle! {
+            []
+            $(#[$attr])*
+            $vis trait $name {
+                $($(
+                    $(#[$constattr])*
+                    #[allow(non_snake_case)]
+                    fn __guilty_mangle $constname() -> $t;
+                )*)+
+            }
+
+            impl<S: ?Sized $(+ $traitname)+> $name for S {
+                $($(
+                    #[allow(non_snake_case)]
+                    fn __guilty_mangle $constname() -> $t {
+                        $crate::guilty!(<S as $traitname>::$constname)
+                    }
+                )*)+
+            }
+        }
+    };
+}
+
+/// Defines zero-sized marker types carrying const values, for parameterizing generic code by a
+/// constant without const generics
+///
+#[cfg_attr(feature = "real-consts", doc = "```ignore")]
+#[cfg_attr(not(feature = "real-consts"), doc = "```")]
+/// # #[macro_use] extern crate guilt_by_association;
+/// # guilty! { pub trait Dim { const N: usize; } }
+/// guilty_carriers! {
+///     Dim::N: usize;
+///     pub D2 = 2;
+///     pub D3 = 3;
+/// }
+/// # fn f<D: Dim>() -> usize { guilty!(D::N) }
+/// # fn main() {
+/// # assert_eq!(f::<D2>(), 2);
+/// # assert_eq!(f::<D3>(), 3);
+/// # }
+/// ```
+///
+/// defines the unit structs `D2` and `D3`, and implements the trait `Dim` (which must have a
+/// single const `N`, defined with `guilty!`) for them, so that `guilty!(D::N)` inside a
+/// `fn f<D: Dim>()` is 2 for `f::<D2>()`. The structs derive the usual traits (`Clone`, `Copy`,
+/// `Debug`, `Default`, `PartialEq`, `Eq`, `Hash`), and may have attributes.
+#[macro_export]
+macro_rules! guilty_carriers {
+    ($traitname:ident :: $constname:ident : $t:ty; $($(#[$attr:meta])* $vis:vis $name:ident = $value:expr;)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+            $vis struct $name;
+
+            $crate::__guilty_rules! { impl $traitname for $name { const $constname: $t = $value; } }
+        )*
+    };
+}
+
+/// Implements a trait for a type by taking the values of its consts from the type's impl of
+/// another trait
+///
+#[cfg_attr(feature = "real-consts", doc = "```ignore")]
+#[cfg_attr(not(feature = "real-consts"), doc = "```")]
+/// # #[macro_use] extern crate guilt_by_association;
+/// # guilty! { trait Config { const MAX: u32; const NAME: &'static str; } }
+/// # guilty! { trait Limits { const LIMIT: u32; const NAME: &'static str; } }
+/// # struct Struct;
+/// # guilty! { impl Config for Struct { const MAX: u32 = 64; const NAME: &'static str = "s"; } }
+/// guilty_adapt! {
+///     impl Limits for Struct from Config {
+///         LIMIT <- MAX: u32;
+///         NAME <- NAME: &'static str;
+///     }
+/// }
+/// # fn main() {
+/// # assert_eq!(guilty!(<Struct as Limits>::LIMIT), 64);
+/// # assert_eq!(guilty!(<Struct as Limits>::NAME), "s");
+/// # }
+/// ```
+///
+/// implements `Limits` for `Struct`, defining `Limits::LIMIT` as `Config::MAX`, and so on. Both
+/// traits must be defined with `guilty!`. As with any impl, leaving out a const which has no
+/// default in the adapted trait is a compile error.
+#[macro_export]
+macro_rules! guilty_adapt {
+    (impl $traitname:ident for $structname:ident from $sourcename:ident {
+        $($(#[$attr:meta])* $constname:ident <- $sourceconst:ident : $t:ty;)*
+    }) => {
+        $crate::__guilty_rules! {
+            impl $traitname for $structname {
+                $($(#[$attr])* const $constname: $t = $crate::__guilty_rules!(<$structname as $sourcename>::$sourceconst);)*
+            }
+        }
+    };
+}
+
+/// Defines a function which dispatches on the value of a const to the type which has it
+///
+/// ```
+/// # #[macro_use] extern crate guilt_by_association;
+/// # pub trait Handler { fn len(&self) -> usize; }
+/// # guilty! { pub trait Message { const ID: u16; fn parse(payload: &[u8]) -> Self; } }
+/// # macro_rules! message {
+/// #     ($name:ident = $id:expr) => {
+/// #         pub struct $name(usize);
+/// #         impl Handler for $name { fn len(&self) -> usize { self.0 } }
+/// #         guilty! {
+/// #             impl Message for $name {
+/// #                 const ID: u16 = $id;
+/// #                 fn parse(payload: &[u8]) -> $name { $name(payload.len()) }
+/// #             }
+/// #         }
+/// #     };
+/// # }
+/// # message!(Ping = 1);
+/// # message!(Pong = 2);
+/// # message!(Data = 3);
+/// guilty_dispatch! {
+///     pub fn handler(id: u16, payload: &[u8]) -> Option<Box<dyn Handler>> {
+///         <T as Message>::ID for [Ping, Pong, Data] => Some(Box::new(T::parse(payload))),
+///         _ => None,
+///     }
+/// }
+/// # fn main() {
+/// # assert_eq!(handler(3, b"abc").map(|handler| handler.len()), Some(3));
+/// # assert!(handler(4, b"abc").is_none());
+/// # }
+/// ```
+///
+/// defines `handler`, which compares its first argument with `<Ping as Message>::ID`,
+/// `<Pong as Message>::ID`, etc. in turn, and evaluates the expression after `=>` with `T` (or
+/// whichever name is given) standing for the first type that matches, or the expression after
+/// `_ =>` if none does. If two types have the same value, the first one listed wins.
+#[macro_export]
+macro_rules! guilty_dispatch {
+    ($(#[$attr:meta])* $vis:vis fn $name:ident($key:ident : $keytype:ty $(, $arg:ident : $argtype:ty)*) -> $ret:ty {
+        <$alias:ident as $traitname:ident>::$constname:ident for [$($structname:ident),* $(,)*] => $body:expr,
+        _ => $fallback:expr $(,)*
+    }) => {
+        $(#[$attr])*
+        $vis fn $name($key: $keytype $(, $arg: $argtype)*) -> $ret {
+            match &$key {
+                $(
+                    key if *key == $crate::guilty!(<$structname as $traitname>::$constname) => {
+                        #[allow(dead_code)]
+                        type $alias = $structname;
+                        $body
+                    }
+                )*
+                _ => $fallback,
+            }
+        }
+    };
+}
+
+/// Asserts that no two of a list of types have the same value of a const
+///
+/// `guilty_assert_unique!(Trait::CONST for A, B, C)` panics, naming both types, if two of the
+/// impls agree on `CONST` (which must be `PartialEq + Debug`). The trait may be left out, as
+/// `guilty_assert_unique!(CONST for A, B, C)`, if that is unambiguous. Since the consts are not
+/// really consts, this cannot fail the compilation: call it from a test that lists every
+/// implementor (together with `guilty!(assert_overridden ...)`, say).
+#[macro_export]
+macro_rules! guilty_assert_unique {
+    ($traitname:ident :: $constname:ident for $($structname:ident),+ $(,)*) => {
+        $crate::guilty_assert_unique!(INTERNAL: concat!(stringify!($traitname), "::", stringify!($constname)),
+                              $((stringify!($structname), $crate::guilty!(<$structname as $traitname>::$constname))),+)
+    };
+    ($constname:ident for $($structname:ident),+ $(,)*) => {
+        $crate::guilty_assert_unique!(INTERNAL: stringify!($constname), $((stringify!($structname), $crate::guilty!($structname::$constname))),+)
+    };
+    (INTERNAL: $constname:expr, $($value:expr),+) => {{
+        let values = [$($value),+];
+        for (i, &(name, ref value)) in values.iter().enumerate() {
+            for &(other, ref other_value) in &values[..i] {
+                assert!(value != other_value, "{} and {} have the same value of {}: {:?}", other, name, $constname, value);
+            }
+        }
+    }};
+}
+
+/// Asserts that a const is equal to another one, or to a value
+///
+/// `guilty_assert_eq!(<A as Trait>::CONST, <B as Trait>::CONST)` is `assert_eq!` of the two
+/// consts, accessed through `guilty!` (so it works the same with any backend), with the two
+/// accesses in the panic message. The second argument may also be any expression, as in
+/// `guilty_assert_eq!(Struct::CONST, 42)`, and a message with format arguments may follow, as
+/// with `assert_eq!`. The consts are written in one of the forms `<Type as Trait>::CONST`,
+/// `<Type>::CONST` or `Struct::CONST`.
+#[macro_export]
+macro_rules! guilty_assert_eq {
+    (INTERNAL: $left:tt, <$structname:ty as $traitname:path>::$constname:ident $(,)*) => {
+        $crate::guilty_assert_eq!(INTERNAL: $left, const [<$structname as $traitname>::$constname])
+    };
+    (INTERNAL: $left:tt, <$structname:ty as $traitname:path>::$constname:ident, $($arg:tt)+) => {
+        $crate::guilty_assert_eq!(INTERNAL: $left, const [<$structname as $traitname>::$constname], $($arg)+)
+    };
+    (INTERNAL: $left:tt, <$structname:ty>::$constname:ident $(,)*) => {
+        $crate::guilty_assert_eq!(INTERNAL: $left, const [<$structname>::$constname])
+    };
+    (INTERNAL: $left:tt, <$structname:ty>::$constname:ident, $($arg:tt)+) => {
+        $crate::guilty_assert_eq!(INTERNAL: $left, const [<$structname>::$constname], $($arg)+)
+    };
+    (INTERNAL: $left:tt, $structname:ident :: $constname:ident $(,)*) => {
+        $crate::guilty_assert_eq!(INTERNAL: $left, const [$structname::$constname])
+    };
+    (INTERNAL: $left:tt, $structname:ident :: $constname:ident, $($arg:tt)+) => {
+        $crate::guilty_assert_eq!(INTERNAL: $left, const [$structname::$constname], $($arg)+)
+    };
+    // both sides are consts: the default message names them
+    (INTERNAL: [$($left:tt)*], const [$($right:tt)*]) => {
+        assert_eq!($crate::guilty!($($left)*), $crate::guilty!($($right)*),
+                   "{} != {}", stringify!($($left)*), stringify!($($right)*))
+    };
+    (INTERNAL: [$($left:tt)*], const [$($right:tt)*], $($arg:tt)+) => {
+        assert_eq!($crate::guilty!($($left)*), $crate::guilty!($($right)*), $($arg)+)
+    };
+    // the right side is a value
+    (INTERNAL: [$($left:tt)*], $right:expr $(,)*) => {
+        assert_eq!($crate::guilty!($($left)*), $right, "{} != {}", stringify!($($left)*), stringify!($right))
+    };
+    (INTERNAL: [$($left:tt)*], $right:expr, $($arg:tt)+) => {
+        assert_eq!($crate::guilty!($($left)*), $right, $($arg)+)
+    };
+
+    (<$structname:ty as $traitname:path>::$constname:ident, $($rest:tt)+) => {
+        $crate::guilty_assert_eq!(INTERNAL: [<$structname as $traitname>::$constname], $($rest)+)
+    };
+    (<$structname:ty>::$constname:ident, $($rest:tt)+) => {
+        $crate::guilty_assert_eq!(INTERNAL: [<$structname>::$constname], $($rest)+)
+    };
+    ($structname:ident :: $constname:ident, $($rest:tt)+) => {
+        $crate::guilty_assert_eq!(INTERNAL: [$structname::$constname], $($rest)+)
+    };
+}
+
+/// Checks the type of a const at compile time
+///
+/// `guilty_assert_type!(Struct::CONST: Type)` (or `<Struct as Trait>::CONST: Type`, or
+/// `<Struct>::CONST: Type`) fails to compile unless the const is of exactly the given type (no
+/// coercions are applied, so a `&'static [u8; 4]` is not a `&'static [u8]`). The const is not
+/// evaluated. It may be used where an item or a statement may, but (like an item) not with the
+/// generic parameters of an enclosing function.
+#[macro_export]
+macro_rules! guilty_assert_type {
+    (<$structname:ty as $traitname:path>::$constname:ident : $t:ty) => {
+        $crate::guilty_assert_type!(INTERNAL: [<$structname as $traitname>::$constname], $t);
+    };
+    (<$structname:ty>::$constname:ident : $t:ty) => {
+        $crate::guilty_assert_type!(INTERNAL: [<$structname>::$constname], $t);
+    };
+    ($structname:ident :: $constname:ident : $t:ty) => {
+        $crate::guilty_assert_type!(INTERNAL: [$structname::$constname], $t);
+    };
+    (INTERNAL: [$($access:tt)*], $t:ty) => {
+        const _: () = {
+            fn type_of<T>(_: &T) -> $crate::__core::marker::PhantomData<T> {
+                $crate::__core::marker::PhantomData
+            }
+            #[allow(dead_code)]
+            fn check() {
+                let _: $crate::__core::marker::PhantomData<$t> = type_of(&$crate::guilty!($($access)*));
+            }
+        };
+    };
+}
+
+/// Chooses a value according to which of a set of mutually exclusive cargo features is enabled
+///
+/// ```
+/// # #[macro_use] extern crate guilt_by_association;
+/// # guilty! { trait Renderer { const BACKEND: &'static str; } }
+/// # struct Window;
+/// guilty! {
+///     impl Renderer for Window {
+///         const BACKEND: &'static str = guilty_select!(
+///             "backend-gl" => "gl", "backend-vk" => "vulkan", default => "software"
+///         );
+///     }
+/// }
+/// # fn main() {
+/// # assert_eq!(guilty!(Window::BACKEND), "software");
+/// # }
+/// ```
+///
+/// is `"gl"` if the `backend-gl` feature (of the crate using the macro) is enabled, and so on, and
+/// `"software"` if none of them is. Without the `default => value` at the end, it is a compile
+/// error if none of the features is enabled. If more than one is enabled, it is always a compile error.
+#[macro_export]
+macro_rules! guilty_select {
+    ($($feature:literal => $value:expr),+ $(, default => $default:expr)* $(,)*) => {{
+        #[cfg(not(any($(feature = $feature),+)))]
+        let value = $crate::guilty_select!(INTERNAL: DEFAULT, [$($feature)+], $($default)*);
+        $(
+            #[cfg(feature = $feature)]
+            let value = $value;
+        )+
+        $crate::guilty_select!(INTERNAL: EXCLUSIVE, $($feature)+);
+        value
+    }};
+
+    // default: the value if none of the features is enabled
+    (INTERNAL: DEFAULT, [$($feature:literal)+], $default:expr) => {
+        $default
+    };
+    (INTERNAL: DEFAULT, [$first:literal $($feature:literal)*],) => {
+        compile_error!(concat!("one of the features \"", $first, "\"", $(", \"", $feature, "\"",)* " must be enabled"))
+    };
+
+    // exclusive: an error for each pair of features which are both enabled
+    (INTERNAL: EXCLUSIVE, $first:literal $($rest:literal)*) => {
+        $(
+            #[cfg(all(feature = $first, feature = $rest))]
+            compile_error!(concat!("the features \"", $first, "\" and \"", $rest, "\" cannot both be enabled"));
+        )*
+        $crate::guilty_select!(INTERNAL: EXCLUSIVE, $($rest)*);
+    };
+    (INTERNAL: EXCLUSIVE,) => {};
+}
+
+/// Rewrites the const accesses in an expression or a block
+///
+#[cfg_attr(feature = "real-consts", doc = "```ignore")]
+#[cfg_attr(not(feature = "real-consts"), doc = "```")]
+/// # #[macro_use] extern crate guilt_by_association;
+/// # guilty! { trait Shape { const CORNERS: u32; } }
+/// # struct Circle;
+/// # guilty! { impl Circle { const PI: f64 = 3.0; } }
+/// # struct Square;
+/// # guilty! { impl Shape for Square { const CORNERS: u32 = 4; } }
+/// # fn main() {
+/// # let r = 2.0;
+/// let area = guilty_expr!(Circle::PI * r * r + <Square as Shape>::CORNERS as f64);
+/// # assert_eq!(area, 16.0);
+/// # }
+/// ```
+///
+/// is the same as `guilty!(Circle::PI) * r * r + guilty!(<Square as Shape>::CORNERS) as f64`.
+/// Every path of the form `Type::NAME` or `<Type as Trait>::NAME` which is not called, or
+/// continued by another `::`, is taken to be a const, including in nested blocks and parentheses
+/// (the input may also be a sequence of statements, as in a block), and in the arguments of the
+/// standard macros which take expressions, such as `assert_eq!`, `format!`, `println!`, `write!`
+/// and `vec!` (called by their bare names), but not in the arguments of other macro calls, which
+/// may not be expressions at all. Since a macro can't tell consts from other items by their names, anything else
+/// written that way, such as `u32::MAX` or a unit enum variant, must be kept out of the rewrite
+/// with a longer path, as in `std::u32::MAX` or `self::Ordering::Less`, or with `<u32>::MAX`.
+/// The input is walked one token at a time, so a long one may need a higher
+/// `#![recursion_limit]`.
+#[macro_export]
+macro_rules! guilty_expr {
+    // The first square brackets hold a stack of the groups the walk is in, with the output so far
+    // and the rest of the input outside each of them. The second square brackets hold the output
+    // so far in the current group.
+
+    // qualified: rewrite <Type as Trait>::NAME (unless it is called or continued)
+    (INTERNAL: $stack:tt, [$($out:tt)*], <$structname:ty as $traitname:path> :: $name:ident ($($args:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: $stack, [$($out)* <$structname as $traitname>::$name], ($($args)*) $($rest)*)
+    };
+    (INTERNAL: $stack:tt, [$($out:tt)*], <$structname:ty as $traitname:path> :: $name:ident :: $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: $stack, [$($out)* <$structname as $traitname>::$name], :: $($rest)*)
+    };
+    (INTERNAL: $stack:tt, [$($out:tt)*], <$structname:ty as $traitname:path> :: $name:ident $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: $stack, [$($out)* $crate::__guilty_rules!(<$structname as $traitname>::$name)], $($rest)*)
+    };
+    // continued: pass through the rest of a longer path, one segment at a time
+    (INTERNAL: $stack:tt, [$($out:tt)*], :: $segment:ident $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: $stack, [$($out)* :: $segment], $($rest)*)
+    };
+    // relative: paths starting with self or super are never consts of a type
+    (INTERNAL: $stack:tt, [$($out:tt)*], self :: $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: $stack, [$($out)* self], :: $($rest)*)
+    };
+    (INTERNAL: $stack:tt, [$($out:tt)*], super :: $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: $stack, [$($out)* super], :: $($rest)*)
+    };
+    // plain: rewrite Type::NAME (unless it is called, continued or a macro)
+    (INTERNAL: $stack:tt, [$($out:tt)*], $structname:ident :: $name:ident ($($args:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: $stack, [$($out)* $structname::$name], ($($args)*) $($rest)*)
+    };
+    (INTERNAL: $stack:tt, [$($out:tt)*], $structname:ident :: $name:ident :: $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: $stack, [$($out)* $structname::$name], :: $($rest)*)
+    };
+    (INTERNAL: $stack:tt, [$($out:tt)*], $structname:ident :: $name:ident ! $args:tt $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: $stack, [$($out)* $structname::$name ! $args], $($rest)*)
+    };
+    (INTERNAL: $stack:tt, [$($out:tt)*], $structname:ident :: $name:ident $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: $stack, [$($out)* $crate::__guilty_rules!($structname::$name)], $($rest)*)
+    };
+    // std-macro: walk the arguments of the standard macros which take expressions (and format
+    //   strings), as a group which is turned back into the call when it ends
+    (INTERNAL: [$($stack:tt)*], $out:tt, assert ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[assert ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, assert_eq ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[assert_eq ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, assert_ne ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[assert_ne ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, debug_assert ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[debug_assert ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, debug_assert_eq ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[debug_assert_eq ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, debug_assert_ne ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[debug_assert_ne ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, dbg ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[dbg ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, format ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[format ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, format_args ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[format_args ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, print ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[print ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, println ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[println ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, eprint ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[eprint ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, eprintln ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[eprintln ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, panic ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[panic ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, write ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[write ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, writeln ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[writeln ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, unreachable ! ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[unreachable ! () $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, vec ! [$($inner:tt)*] $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[vec ! [] $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    // macro: pass through any other macro call, without walking its arguments
+    (INTERNAL: $stack:tt, [$($out:tt)*], $mac:ident ! $args:tt $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: $stack, [$($out)* $mac ! $args], $($rest)*)
+    };
+    // group: walk the inside of a group, saving the current state on the stack
+    (INTERNAL: [$($stack:tt)*], $out:tt, ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[() $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, [$($inner:tt)*] $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[[] $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    (INTERNAL: [$($stack:tt)*], $out:tt, {$($inner:tt)*} $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [[{} $out [$($rest)*]] $($stack)*], [], $($inner)*)
+    };
+    // other: pass through any other token
+    (INTERNAL: $stack:tt, [$($out:tt)*], $token:tt $($rest:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: $stack, [$($out)* $token], $($rest)*)
+    };
+    // group-end: wrap the output of a group, and continue after it
+    (INTERNAL: [[() [$($out:tt)*] [$($rest:tt)*]] $($stack:tt)*], [$($inner:tt)*],) => {
+        $crate::guilty_expr!(INTERNAL: [$($stack)*], [$($out)* ($($inner)*)], $($rest)*)
+    };
+    (INTERNAL: [[[] [$($out:tt)*] [$($rest:tt)*]] $($stack:tt)*], [$($inner:tt)*],) => {
+        $crate::guilty_expr!(INTERNAL: [$($stack)*], [$($out)* [$($inner)*]], $($rest)*)
+    };
+    (INTERNAL: [[{} [$($out:tt)*] [$($rest:tt)*]] $($stack:tt)*], [$($inner:tt)*],) => {
+        $crate::guilty_expr!(INTERNAL: [$($stack)*], [$($out)* {$($inner)*}], $($rest)*)
+    };
+    (INTERNAL: [[$mac:ident ! () [$($out:tt)*] [$($rest:tt)*]] $($stack:tt)*], [$($inner:tt)*],) => {
+        $crate::guilty_expr!(INTERNAL: [$($stack)*], [$($out)* $mac!($($inner)*)], $($rest)*)
+    };
+    (INTERNAL: [[$mac:ident ! [] [$($out:tt)*] [$($rest:tt)*]] $($stack:tt)*], [$($inner:tt)*],) => {
+        $crate::guilty_expr!(INTERNAL: [$($stack)*], [$($out)* $mac![$($inner)*]], $($rest)*)
+    };
+    // done: output the rewritten input as a block
+    (INTERNAL: [], [$($out:tt)*],) => {
+        { $($out)* }
+    };
+
+    // start with an empty stack and output (last, as it matches anything)
+    ($($input:tt)*) => {
+        $crate::guilty_expr!(INTERNAL: [], [], $($input)*)
+    };
+}
+
+/// Rewrites the traits and impls in a module
+///
+#[cfg_attr(feature = "real-consts", doc = "```ignore")]
+#[cfg_attr(not(feature = "real-consts"), doc = "```")]
+/// # #[macro_use] extern crate guilt_by_association;
+/// guilty_mod! {
+///     pub mod config {
+///         pub trait Limits { const MAX: usize; }
+///         pub struct Small;
+///         impl Limits for Small { const MAX: usize = 16; }
+///     }
+/// }
+/// # fn main() {
+/// # assert_eq!(guilty!(<config::Small as config::Limits>::MAX), 16);
+/// # }
+/// ```
+///
+/// is the same as wrapping each trait and impl of `config` (and of any modules nested in it) in
+/// `guilty!`, passing everything else through as it is. The contents of the module may also be
+/// given alone, without the `mod`. The items are split off one token tree at a time, so a big
+/// module may need a higher `#![recursion_limit]`.
+#[macro_export]
+macro_rules! guilty_mod {
+    // The square brackets hold the attributes and visibility of the item being split off.
+
+    // attrs: collect the attributes and visibility of an item
+    (INTERNAL: [$($prefix:tt)*], # [$($attr:tt)*] $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: [$($prefix)* #[$($attr)*]], $($rest)*);
+    };
+    (INTERNAL: [$($prefix:tt)*], pub ($($restr:tt)*) $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: [$($prefix)* pub ($($restr)*)], $($rest)*);
+    };
+    (INTERNAL: [$($prefix:tt)*], pub $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: [$($prefix)* pub], $($rest)*);
+    };
+    // guilty: a trait or impl goes up to its body, and is rewritten
+    (INTERNAL: $prefix:tt, trait $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: GUILTY, $prefix, [trait], $($rest)*);
+    };
+    (INTERNAL: $prefix:tt, impl $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: GUILTY, $prefix, [impl], $($rest)*);
+    };
+    (INTERNAL: $prefix:tt, unsafe trait $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: GUILTY, $prefix, [unsafe trait], $($rest)*);
+    };
+    (INTERNAL: $prefix:tt, unsafe impl $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: GUILTY, $prefix, [unsafe impl], $($rest)*);
+    };
+    (INTERNAL: $prefix:tt, extend $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: GUILTY, $prefix, [extend], $($rest)*);
+    };
+    (INTERNAL: GUILTY, [$($prefix:tt)*], [$($item:tt)*], { $($body:tt)* } $($rest:tt)*) => {
+        $crate::__guilty_rules! { $($prefix)* $($item)* { $($body)* } }
+        $crate::guilty_mod!(INTERNAL: [], $($rest)*);
+    };
+    (INTERNAL: GUILTY, $prefix:tt, [$($item:tt)*], $next:tt $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: GUILTY, $prefix, [$($item)* $next], $($rest)*);
+    };
+    // nested: a module is rewritten in turn
+    (INTERNAL: [$($prefix:tt)*], mod $name:ident { $($body:tt)* } $($rest:tt)*) => {
+        $($prefix)* mod $name {
+            $crate::guilty_mod!(INTERNAL: [], $($body)*);
+        }
+        $crate::guilty_mod!(INTERNAL: [], $($rest)*);
+    };
+    // value: a const, static, use or type alias goes up to the semicolon (its value, or the list
+    //    of names imported, may contain blocks)
+    (INTERNAL: $prefix:tt, const $name:ident : $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: VALUE, $prefix, [const $name :], $($rest)*);
+    };
+    (INTERNAL: $prefix:tt, static $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: VALUE, $prefix, [static], $($rest)*);
+    };
+    (INTERNAL: $prefix:tt, use $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: VALUE, $prefix, [use], $($rest)*);
+    };
+    (INTERNAL: $prefix:tt, type $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: VALUE, $prefix, [type], $($rest)*);
+    };
+    (INTERNAL: VALUE, [$($prefix:tt)*], [$($item:tt)*], ; $($rest:tt)*) => {
+        $($prefix)* $($item)* ;
+        $crate::guilty_mod!(INTERNAL: [], $($rest)*);
+    };
+    (INTERNAL: VALUE, $prefix:tt, [$($item:tt)*], $next:tt $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: VALUE, $prefix, [$($item)* $next], $($rest)*);
+    };
+    // other: any other item goes up to a semicolon or a braced block, and is passed through
+    (INTERNAL: [$($prefix:tt)*], $next:tt $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: OTHER, [$($prefix)*], [$next], $($rest)*);
+    };
+    (INTERNAL: OTHER, [$($prefix:tt)*], [$($item:tt)*], ; $($rest:tt)*) => {
+        $($prefix)* $($item)* ;
+        $crate::guilty_mod!(INTERNAL: [], $($rest)*);
+    };
+    (INTERNAL: OTHER, [$($prefix:tt)*], [$($item:tt)*], { $($body:tt)* } $($rest:tt)*) => {
+        $($prefix)* $($item)* { $($body)* }
+        $crate::guilty_mod!(INTERNAL: [], $($rest)*);
+    };
+    (INTERNAL: OTHER, $prefix:tt, [$($item:tt)*], $next:tt $($rest:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: OTHER, $prefix, [$($item)* $next], $($rest)*);
+    };
+    // done: all items are seen
+    (INTERNAL: [],) => {};
+
+    ($(#[$attr:meta])* $vis:vis mod $name:ident { $($body:tt)* }) => {
+        $(#[$attr])* $vis mod $name {
+            $crate::guilty_mod!(INTERNAL: [], $($body)*);
+        }
+    };
+    ($($items:tt)*) => {
+        $crate::guilty_mod!(INTERNAL: [], $($items)*);
+    };
+}
+
+// stands in for the typenum crate (as ::typenum) in the tests of the typenum option
+#[cfg(all(test, not(feature = "real-consts")))]
+mod typenum {
+    pub trait Unsigned { fn to_usize() -> usize; }
+    pub mod consts {
+        pub struct U4;
+        pub struct U32;
+        impl super::Unsigned for U4 { fn to_usize() -> usize { 4 } }
+        impl super::Unsigned for U32 { fn to_usize() -> usize { 32 } }
+    }
+}
+
+#[cfg(test)]
+#[allow(dead_code)]
+mod tests {
+    #[cfg(feature = "proc-macro")]
+    use guilty;
+
+    // some small tests
+    guilty! { trait Empty { } }
+    guilty! { trait JustFn { fn foo(&self); } }
+    guilty! { trait JustType { type Foo; } }
+    guilty! { pub trait JustConst { const FOO: (); } }
+    guilty! { trait DocFn { #[doc="bar"] fn foo(&self); } }
+    guilty! { pub(crate) trait DocType { #[doc="bar"] type Foo; } }
+    guilty! { trait DocConst { #[doc="bar"] const FOO: (); } }
+    struct Foo;
+    guilty! { impl Empty for Foo { } }
+    guilty! { impl JustFn for Foo { fn foo(&self) {} } }
+    guilty! { impl JustType for Foo { type Foo = (); } }
+    guilty! { impl JustConst for Foo { const FOO: () = (); } }
+    guilty! { impl DocFn for Foo { #[doc="bar"] fn foo(&self) {} } }
+    guilty! { impl DocType for Foo { #[doc="bar"] type Foo = (); } }
+    guilty! { impl DocConst for Foo { #[doc="bar"] const FOO: () = (); } }
+
+    guilty! { trait Layout { const SIZE: usize; const ALIGN: usize; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(auto_layout)] impl Layout for Foo { } }
+    struct Bar(u64, u8);
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(auto_layout)] impl Layout for Bar { } }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(fingerprint)] trait Config { const LIMIT: u32; const NAME: &'static str; } }
+    struct Host;
+    struct Plugin;
+    struct Drifted;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(fingerprint)] impl Config for Host { const LIMIT: u32 = 8; const NAME: &'static str = "x"; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(fingerprint)] impl Config for Plugin { const LIMIT: u32 = 8; const NAME: &'static str = "x"; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(fingerprint)] impl Config for Drifted { const LIMIT: u32 = 9; const NAME: &'static str = "x"; } }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(erased = ErasedMeta)]
+        pub trait Meta {
+            /// The plugin name
+            const NAME: &'static str;
+            const VERSION: u32 = 1;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { impl Meta for Host { const NAME: &'static str = "host"; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { impl Meta for Plugin { const NAME: &'static str = "plugin"; const VERSION: u32 = 2; } }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(aggregates(max, min, sum), name_of)]
+        trait Thresholds {
+            const WARN: u32;
+            const THROTTLE: u32 = 100;
+            const REJECT: u32;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { impl Thresholds for Foo { const WARN: u32 = 10; const REJECT: u32 = 50; } }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(track_overrides)]
+        trait Backend {
+            const ID: u16 = 0;
+            const NAME: &'static str = "backend";
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(track_overrides)] impl Backend for Host { const ID: u16 = 1; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(track_overrides)] impl Backend for Plugin { const NAME: &'static str = "plugin"; } }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(invariant(guilty!(Self::MIN) <= guilty!(Self::MAX)))]
+        #[guilty(invariant(guilty!(Self::MAX) < 1000))]
+        trait Range {
+            const MIN: u32;
+            const MAX: u32;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(check_invariants)] impl Range for Foo { const MIN: u32 = 1; const MAX: u32 = 10; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(check_invariants)] impl Range for Bar { const MIN: u32 = 10; const MAX: u32 = 1; } }
+    #[cfg(not(feature = "real-consts"))]
+    fn try_max<T>() -> u32 {
+        guilty_try_const!(<T as Range>::MAX: u32 else 0)
+    }
+
+    // the invariants methods are bounded like the consts
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(invariant(guilty!(Self::SLOTS) > 0), object_safe)]
+        trait Capacity {
+            const SLOTS: usize;
+            fn slots(&self) -> usize;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { impl Capacity for Foo { const SLOTS: usize = 4; fn slots(&self) -> usize { 4 } } }
+
+    // a trait which is not defined with guilty! can still be implemented with it, keeping the
+    // names as they are
+    trait Handwritten {
+        #[allow(non_snake_case)]
+        fn LIMIT() -> u8;
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(unmangled)] impl Handwritten for Foo { const LIMIT: u8 = 3; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(unmangled)] impl Handwritten for Bar { const LIMIT: u8 = 3 + 1; } }
+
+    guilty! { trait Retry { const TIMEOUT_MS: u64; const RETRIES: u8 = 3; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(overridable)] impl Retry for Foo { const TIMEOUT_MS: u64 = 500; } }
+
+    guilty! { trait Pool { #[deprecated] const MAX_CONN: u32 = 16; } }
+    guilty! { impl Pool for Host { } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty_alias! {
+        impl Host {
+            /// The maximum number of connections
+            pub const CONNECTIONS: u32 = <Host as Pool>::MAX_CONN;
+            const POOL_SIZE: u32 = Host::CONNECTIONS;
+        }
+    }
+
+    guilty! { trait Codec { const TAG: u8; } }
+    guilty! { trait Versioned { const MAJOR: u16; const MINOR: u16 = 0; } }
+    guilty_compose! {
+        trait Wire {
+            use Codec { const TAG: u8; }
+            use Versioned { const MAJOR: u16; const MINOR: u16; }
+        }
+    }
+    struct Msg;
+    guilty! { impl Codec for Msg { const TAG: u8 = 0x2a; } }
+    guilty! { impl Versioned for Msg { const MAJOR: u16 = 3; } }
+    #[cfg(not(feature = "real-consts"))]
+    fn wire_header<T: Wire>() -> (u8, u16, u16) {
+        (guilty!(T::TAG), guilty!(T::MAJOR), guilty!(T::MINOR))
+    }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        impl Retry for Bar {
+            #[guilty(env = "GUILT_BY_ASSOCIATION_TEST_TIMEOUT")]
+            /// Tunable in staging
+            const TIMEOUT_MS: u64 = 500;
+            #[guilty(env = "GUILT_BY_ASSOCIATION_TEST_RETRIES")]
+            const RETRIES: u8 = 3;
+        }
+    }
+
+    guilty! { trait Dim { const N: usize; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty_carriers! {
+        Dim::N: usize;
+        /// Points in the plane
+        D2 = 2;
+        pub D3 = 1 + 2;
+    }
+    fn origin<D: Dim>() -> Vec<f32> {
+        vec![0.0; guilty!(D::N)]
+    }
+
+    guilty! { trait Quota { const LIMIT: u32; const LABEL: &'static str; const BURST: u32 = 1; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty_adapt! {
+        impl Quota for Drifted from Config {
+            LIMIT <- LIMIT: u32;
+            /// Reuses the config name
+            LABEL <- NAME: &'static str;
+        }
+    }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(provenance)]
+        trait Protocol {
+            const VERSION: u8 = 1;
+            const PORT: u16;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(provenance)] impl Protocol for Msg { const PORT: u16 = 8080; } }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        trait Sink {
+            const BATCH: usize;
+            const DURABLE: bool = false;
+            type Item;
+            fn describe(&self, verbose: bool) -> String;
+            fn flush(&mut self) -> usize;
+            fn kind() -> &'static str { "sink" }
+            fn mode(&self) -> &'static str;
+            static OPENED: ::std::sync::atomic::AtomicUsize;
+        }
+    }
+    struct Log(Vec<u8>);
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(also_for_ref(mut, box))]
+        impl Sink for Log {
+            const BATCH: usize = 8;
+            type Item = u8;
+            fn describe(&self, verbose: bool) -> String {
+                if verbose { format!("log of {}", self.0.len()) } else { "log".to_string() }
+            }
+            fn flush(&mut self) -> usize { let n = self.0.len(); self.0.clear(); n }
+            fn kind() -> &'static str { "log" }
+            /// The configuration the log was built in
+            #[cfg(test)]
+            fn mode(&self) -> &'static str { "test" }
+            #[cfg(not(test))]
+            fn mode(&self) -> &'static str { "release" }
+            static OPENED: ::std::sync::atomic::AtomicUsize = ::std::sync::atomic::AtomicUsize::new(0);
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(also_for_ref)] impl Codec for Log { const TAG: u8 = 1; } }
+    #[cfg(not(feature = "real-consts"))]
+    fn batch_of<S: Sink>(_: &S) -> usize {
+        guilty!(S::BATCH)
+    }
+    fn tag_of<C: Codec>(_: C) -> u8 {
+        guilty!(C::TAG)
+    }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(erased = ErasedSecrets, name_of, track_overrides, provenance, fingerprint)]
+        trait Secrets {
+            const PUBLIC: u32;
+            #[guilty(no_reflect)]
+            /// Never shown
+            const SALT: u32 = 0;
+        }
+    }
+    struct Vault;
+    struct Keyring;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(fingerprint, track_overrides, provenance)]
+        impl Secrets for Vault {
+            const PUBLIC: u32 = 1;
+            #[guilty(no_reflect)]
+            const SALT: u32 = 99;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(fingerprint, track_overrides, provenance)]
+        impl Secrets for Keyring {
+            const PUBLIC: u32 = 1;
+            #[guilty(no_reflect)]
+            #[guilty(env = "GUILT_BY_ASSOCIATION_TEST_SALT")]
+            const SALT: u32 = 42;
+        }
+    }
+
+    #[cfg(not(feature = "real-consts"))]
+    mod pool {
+        #[cfg(feature = "proc-macro")]
+        use guilty;
+
+        guilty! { pub trait Sizes { const SMALL: usize; const LARGE: usize = 64; } }
+        pub struct Pool;
+        guilty! { #[guilty(inherent = pub(crate))] impl Sizes for Pool { const SMALL: usize = 4; } }
+    }
+
+    guilty! { trait Build { const PROFILE: &'static str; } }
+    guilty! { impl Build for Host { const PROFILE: &'static str = guilty_select!("provenance" => "debug", default => "release"); } }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty_dispatch! {
+        fn backend_name(id: u16, suffix: &str) -> Option<String> {
+            <T as Backend>::ID for [Host, Plugin] => Some(format!("{}{}", guilty!(<T as Backend>::NAME), suffix)),
+            _ => None,
+        }
+    }
+
+    guilty2! {
+        /// A trait in the strict dialect
+        pub trait Shape<T: Copy>: Sized where T: Into<f64> {
+            fn area(&self) -> f64;
+            const SIDES: u32;
+            /// Scale factor
+            const SCALE: T;
+            fn scaled(&self) -> f64 { self.area() * guilty2!(Self::SCALE).into() }
+            type Unit;
+            const NAME: &'static str = "shape";
+            fn describe<'a>(&'a self, prefix: &'a str) -> (&'a str, u32) where Self: 'a { (prefix, guilty2!(Self::SIDES)) }
+        }
+    }
+
+    struct Square<T>(T);
+
+    guilty2! {
+        impl<T: Copy + Into<f64>> Shape<f32> for Square<T> {
+            const SIDES: u32 = 4;
+            type Unit = ();
+            fn area(&self) -> f64 { let side = self.0.into(); side * side }
+            const SCALE: f32 = 0.5;
+        }
+    }
+
+    // methods with arbitrary self types after the consts
+    guilty! {
+        trait Task {
+            const PRIORITY: u8;
+            fn run(self: Box<Self>) -> u8;
+            #[inline]
+            fn share(self: ::std::rc::Rc<Self>) -> u8 { guilty!(Self::PRIORITY) }
+        }
+    }
+    guilty! {
+        impl Task for Foo {
+            const PRIORITY: u8 = 2;
+            fn run(self: Box<Self>) -> u8 { 1 }
+            fn share(self: ::std::rc::Rc<Self>) -> u8 { 3 }
+        }
+    }
+    guilty! { impl Task for Bar { const PRIORITY: u8 = 5; #[inline] fn run(self: Box<Self>) -> u8 { self.1 } } }
+    guilty2! {
+        trait Job {
+            fn run(self: ::std::sync::Arc<Self>) -> u8;
+            const PRIORITY: u8 = 7;
+            fn pinned(self: ::std::pin::Pin<&mut Self>) -> u8 { guilty2!(Self::PRIORITY) }
+        }
+    }
+    guilty2! { impl Job for Foo { fn run(self: ::std::sync::Arc<Self>) -> u8 { 6 } } }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(consts_mod = buffer_consts)]
+        trait Buffer {
+            const CAPACITY: usize;
+            const GROWABLE: bool = false;
+
+            #[allow(non_snake_case)]
+            fn CAPACITY(&self) -> usize;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(consts_mod = buffer_consts)]
+        impl Buffer for Foo {
+            const CAPACITY: usize = 16;
+
+            fn CAPACITY(&self) -> usize { 0 }
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    fn capacity<B: Buffer>() -> usize {
+        guilty!(<B as buffer_consts::Buffer>::CAPACITY)
+    }
+
+    guilty! { trait Fixed { const LEN: usize; const ZEROED: bool = false; fn first(&self) -> Option<i32>; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        impl Fixed for [T; 0..=32] where T: Copy + Into<i32> {
+            const ZEROED: bool = true;
+            fn first(&self) -> Option<i32> { self.iter().next().map(|&x| x.into()) }
+        }
+    }
+
+    guilty! { trait Borrowed<'a> { const NAME: &'a str; fn name(&self) -> &'a str { guilty!(Self::NAME) } } }
+    guilty! { trait Nicknamed<'a>: Borrowed<'a> { const NICKNAME: &'a str = "nick"; } }
+    guilty! { impl<'a> Borrowed<'a> for Foo { const NAME: &'a str = "foo"; } }
+    guilty! { impl<'a> Nicknamed<'a> for Foo { } }
+    struct View<'a>(&'a str);
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(inherent)]
+        impl<'a> Borrowed<'a> for View<'a> {
+            const NAME: &'a str = "view";
+            fn name(&self) -> &'a str { self.0 }
+        }
+    }
+
+    #[derive(Clone)]
+    struct Wrapper<T>(T);
+    guilty! { trait Arity { const N: usize; fn arity(&self) -> usize { guilty!(Self::N) } } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(also_for_ref)]
+        impl<T> Arity for Wrapper<T> where T: Clone {
+            const N: usize = 1;
+        }
+    }
+    guilty! { impl<T: Into<Vec<u8>>, U> Arity for (Wrapper<T>, U) { const N: usize = 2; } }
+
+    guilty! {
+        trait Cloned: Arity where Self: Clone {
+            const COPIES: usize = 2;
+            fn copies(&self) -> Vec<Self> { vec![self.clone(); guilty!(Self::COPIES) * guilty!(Self::N)] }
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { impl<T> Cloned for Wrapper<T> where T: Clone { } }
+
+    // consts mixed with the other items
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(track_overrides, consts_mod = mixed_consts)]
+        trait Mixed {
+            fn kind(&self) -> &'static str { "mixed" }
+            const FIRST: u8;
+            type Out;
+            fn plus<T: Into<u8>>(&self, t: T) -> u8 where Self: Sized { t.into() + guilty!(Self::SECOND) }
+            const SECOND: u8 = 10;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(track_overrides, consts_mod = mixed_consts)]
+        impl Mixed for Foo {
+            type Out = ();
+            const FIRST: u8 = 1;
+            fn kind(&self) -> &'static str { "foo" }
+            const SECOND: u8 = 20;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(track_overrides, consts_mod = mixed_consts)]
+        impl Mixed for Bar {
+            fn kind(&self) -> &'static str { "bar" }
+            type Out = u8;
+            const FIRST: u8 = 2;
+        }
+    }
+
+    mod shapes { pub struct Dot; }
+    guilty! { trait Footprint { const BYTES: usize; } }
+    guilty! { impl Footprint for (u8, u8) { const BYTES: usize = 2; } }
+    guilty! { impl Footprint for [f32; 3] { const BYTES: usize = 12; } }
+    guilty! { impl Footprint for &'static str { const BYTES: usize = 16; } }
+    guilty! { impl Footprint for Vec<u8> { const BYTES: usize = 24; } }
+    guilty! { impl Footprint for shapes::Dot { const BYTES: usize = 0; } }
+
+    guilty! {
+        trait Render: ::std::fmt::Debug + Clone + Iterator<Item = u8> {
+            const GLYPH: char = '#';
+            fn render(&self) -> String { format!("{}{:?}", guilty!(Self::GLYPH), self.clone().count()) }
+        }
+    }
+    #[derive(Debug, Clone)]
+    struct Countdown(u8);
+    impl Iterator for Countdown {
+        type Item = u8;
+        fn next(&mut self) -> Option<u8> { self.0 = self.0.checked_sub(1)?; Some(self.0) }
+    }
+    guilty! { impl Render for Countdown { } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(consts_mod = tagged_consts)] trait Tagged: Clone + ::std::fmt::Debug { const TAG: u8; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(consts_mod = tagged_consts)] impl Tagged for Countdown { const TAG: u8 = 7; } }
+
+    mod my_traits {
+        #[cfg(feature = "proc-macro")]
+        use guilty;
+
+        guilty! { pub trait Config { const RETRIES: u8; const VERBOSE: bool = false; } }
+    }
+    mod app {
+        #[cfg(feature = "proc-macro")]
+        use guilty;
+
+        pub struct Settings;
+        pub struct Remote;
+        guilty! { impl Settings { pub const TIMEOUT: u32 = 30; } }
+        pub mod client {
+            #[cfg(feature = "proc-macro")]
+            use guilty;
+
+            pub fn timeout() -> u32 { guilty!(super::Settings::TIMEOUT) + guilty!(self::super::Settings::TIMEOUT) }
+        }
+    }
+    guilty! { impl ::tests::my_traits::Config for app::Remote { const RETRIES: u8 = 5; } }
+    guilty! { impl my_traits::Config for app::Settings { const RETRIES: u8 = 3; } }
+    guilty! { impl crate::tests::my_traits::Config for ::tests::Foo { const RETRIES: u8 = 1; const VERBOSE: bool = true; } }
+
+    guilty! { trait Framing { const VERSION: u16; fn extra(); const NAME: &'static str; const MAGIC: u32; const ID: u64; } }
+    struct Frame;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(literals_mod = frame_literals)]
+        impl Framing for Frame {
+            /// The version
+            const VERSION: u16 = 3;
+            fn extra() {}
+            const NAME: &'static str = "frame";
+            const MAGIC: u32 = 0xCAFE << 4;
+            #[guilty(no_reflect)]
+            const ID: u64 = 77;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    static FRAME_BUFFER: [u8; guilty!(literal frame_literals::VERSION) as usize] = [0; 3];
+    #[cfg(not(feature = "real-consts"))]
+    fn frame_version_name(version: u16) -> &'static str {
+        match version {
+            guilty!(literal frame_literals::VERSION) => guilty!(literal frame_literals::NAME),
+            _ => "unknown",
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    const FRAME_LABEL: &str = concat!(frame_literals!(NAME), "/v", frame_literals!(VERSION));
+
+    struct Palette;
+    guilty! { impl Palette { pub const COLORS: usize = 16; const DEPTH: u32 = 4 * 2; } }
+    #[cfg(any(not(feature = "proc-macro"), feature = "const-fn"))]
+    static PALETTE: [u32; guilty!(Palette::COLORS)] = [guilty!(Palette::DEPTH); guilty!(Palette::COLORS)];
+
+    static SQUARES_BUILT: ::std::sync::atomic::AtomicUsize = ::std::sync::atomic::AtomicUsize::new(0);
+    fn build_squares() -> [u64; 16] {
+        SQUARES_BUILT.fetch_add(1, ::std::sync::atomic::Ordering::SeqCst);
+        let mut squares = [0; 16];
+        for (n, square) in squares.iter_mut().enumerate() {
+            *square = (n * n) as u64;
+        }
+        squares
+    }
+    guilty! { trait Tables { const SQUARES: &'static [u64; 16]; } }
+    struct Lookup;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        impl Tables for Lookup {
+            #[guilty(cached)]
+            /// Squares of the nibbles
+            const SQUARES: &'static [u64; 16] = build_squares();
+        }
+    }
+    guilty! { trait Widths { const WIDTHS: &'static Vec<usize>; } }
+    struct Column<T>(T);
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { impl<T> Widths for Column<T> { #[guilty(cached)] const WIDTHS: &'static Vec<usize> = vec![1, 2, 4]; } }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(erased = DynTuning, fingerprint)]
+        trait Tuning {
+            #[guilty(fn_attr(inline(always), must_use))]
+            /// Samples per second
+            const RATE: u32 = 44_100;
+            #[guilty(fn_attr(must_use))] #[guilty(no_reflect)] const KEY: u8;
+        }
+    }
+    struct Studio;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(literals_mod = studio_literals, fingerprint)]
+        impl Tuning for Studio {
+            #[guilty(fn_attr(inline(never)))] const RATE: u32 = 48_000;
+            #[guilty(fn_attr(inline))] #[guilty(no_reflect)] const KEY: u8 = 42;
+        }
+    }
+
+    // the same impl of two versions of a trait, which only differ in a default
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(fingerprint)] trait Retention { const SOFT: u32 = 10; const HARD: u32; } }
+    #[cfg(not(feature = "real-consts"))]
+    mod retention_v2 {
+        #[cfg(feature = "proc-macro")] use guilty;
+        guilty! { #[guilty(fingerprint)] pub trait Retention { const SOFT: u32 = 20; const HARD: u32; } }
+    }
+    struct Tenant;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(fingerprint)] impl Retention for Tenant { const HARD: u32 = 100; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(fingerprint)] impl retention_v2::Retention for Tenant { const HARD: u32 = 100; } }
+
+    guilty! { trait Lanes { const LANES: usize; fn lanes(&self) -> usize { guilty!(Self::LANES) } } }
+    struct Mono;
+    struct Stereo;
+    struct Duplex<A, B>(A, B);
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(inherent)] impl Lanes for Mono, Stereo, Duplex<u8, Duplex<u16, u32>> { const LANES: usize = 4; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { impl<T> Lanes for Vec<T>, Option<T> where T: Copy, { const LANES: usize = 1, } }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(blanket)]
+        /// Layout of anything that can be displayed
+        pub extend trait ::std::fmt::Display as DisplayConsts { const WIDTH: usize = 8, const RIGHT_ALIGNED: bool = false, }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    fn padded<T: ::std::fmt::Display + ?Sized>(value: &T) -> String {
+        format!("{:>1$}", value, guilty!(T::WIDTH))
+    }
+    guilty! { extend trait Iterator as IteratorConsts { const ENDLESS: bool; } }
+    guilty! { impl IteratorConsts for ::std::ops::Range<u32> { const ENDLESS: bool = false; } }
+    guilty! { impl<T: Clone> IteratorConsts for ::std::iter::Repeat<T> { const ENDLESS: bool = true; } }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(object_safe)]
+        trait Pooled {
+            /// Number of live connections
+            static LIVE: ::std::sync::atomic::AtomicUsize;
+            const LIMIT: usize = 2;
+            fn open(&self) -> bool where Self: Sized {
+                guilty!(Self::LIVE).fetch_add(1, ::std::sync::atomic::Ordering::SeqCst) < guilty!(Self::LIMIT)
+            }
+        }
+    }
+    struct Primary;
+    struct Replica;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        impl Pooled for Primary {
+            static LIVE: ::std::sync::atomic::AtomicUsize = ::std::sync::atomic::AtomicUsize::new(0);
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        impl Pooled for Replica {
+            static LIVE: ::std::sync::atomic::AtomicUsize = ::std::sync::atomic::AtomicUsize::new(1);
+            const LIMIT: usize = 8;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { impl Replica { pub static NAMES: [&'static str; 2] = ["eu", "us"]; } }
+
+    fn triple(x: i32) -> i32 { x * 3 }
+    fn build_row() -> [u16; 8] { [1; 8] }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(fingerprint)]
+        trait Layouts: Sized {
+            const KEY: [u8; 32],
+            const SCALE: fn(i32) -> i32 = triple,
+            const NEXT: Option<Self>,
+            const LABELS: &'static [&'static str] = &["x", "y"],
+            const CELLS: Vec<(u8, [u16; 2])>,
+            const MERGE: fn(u8, u8) -> Option<u8>,
+            const ROW: &'static [u16; 8];
+        }
+    }
+    #[derive(Debug, PartialEq)]
+    struct Grid;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(fingerprint, literals_mod = grid_literals)]
+        impl Layouts for Grid {
+            const KEY: [u8; 32] = [7; 32],
+            const NEXT: Option<Self> = Some(Grid),
+            const CELLS: Vec<(u8, [u16; 2])> = vec![(1, [2, 3])],
+            const MERGE: fn(u8, u8) -> Option<u8> = |a, b| a.checked_add(b),
+            #[guilty(cached)] const ROW: &'static [u16; 8] = build_row(),
+        }
+    }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(fingerprint)]
+        trait Stack {
+            const EMPTY: Self::Item;
+            const DEPTH: usize = 4;
+            fn top(&self) -> Self::Item;
+            type Item: Copy;
+        }
+    }
+    struct Shelf;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(fingerprint)]
+        impl Stack for Shelf {
+            const EMPTY: Self::Item = ' ';
+            fn top(&self) -> char { 'z' }
+            type Item = char;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(consts_mod = queue_consts)]
+        trait Queue { const FRONT: Self::Entry; fn back(&self) -> Self::Entry; type Entry; }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(consts_mod = queue_consts)]
+        impl Queue for Shelf { const FRONT: Self::Entry = 1; fn back(&self) -> u16 { 9 } type Entry = u16; }
+    }
+
+    guilty! { trait Blank { const BLANK: Self; const INK: u8; } }
+    #[derive(Debug, PartialEq)]
+    struct Canvas(u8);
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(literals_mod = canvas_literals)]
+        impl Blank for Canvas {
+            #[guilty(impl_default)]
+            /// An empty canvas
+            const BLANK: Self = Canvas(guilty!(literal canvas_literals::INK));
+            const INK: u8 = 3;
+        }
+    }
+    #[derive(Debug, PartialEq)]
+    struct Slot<T>(Option<T>);
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        impl<T> Blank for Slot<T> where T: Clone {
+            #[guilty(fn_attr(inline))] #[guilty(impl_default)] const BLANK: Self = Slot(None);
+            const INK: u8 = 0;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty_assert_type!(<Slot<u8>>::INK: u8);
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(introspect, inherit(Blank { /// How dark it is
+                                             const INK: u8; }))]
+        trait Tinted: Blank { const HUE: u16; }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { impl Tinted for Canvas { const HUE: u16 = 120; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        trait Metal { const DENSITY: u32; fn symbol(&self) -> &'static str; }
+        use std::{fmt, str};
+        #[derive(Debug)]
+        struct Iron;
+        impl fmt::Display for Iron {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str(str::from_utf8(b"iron").unwrap()) }
+        }
+        impl Metal for Iron { const DENSITY: u32 = 7874; fn symbol(&self) -> &'static str { "Fe" } }
+        fn heavier<A: Metal, B: Metal>() -> bool { guilty!(A::DENSITY) > guilty!(B::DENSITY) }
+        struct Gold;
+        #[cfg(test)]
+        impl Metal for Gold { const DENSITY: u32 = 19_300; fn symbol(&self) -> &'static str { "Au" } }
+        #[cfg(not(test))]
+        impl Metal for Gold { const DENSITY: u32 = 0; fn symbol(&self) -> &'static str { "" } }
+        struct Ore<T>(T);
+        #[guilty(inherent)]
+        impl<T> Metal for Ore<T> where T: Metal { const DENSITY: u32 = 0; fn symbol(&self) -> &'static str { "?" } }
+        const ALLOYS: [&'static str; 2] = ["steel", "electrum"];
+    }
+    #[cfg(not(feature = "real-consts"))]
+    mod kingdom {
+        #[cfg(feature = "proc-macro")]
+        use guilty;
 
-#![cfg_attr(not(test), no_std)]
+        pub mod province {
+            #[cfg(feature = "proc-macro")]
+            use guilty;
 
-/// Macro for declaring/implementing traits with fake associated consts
-///
-/// See the [crate-level documentation](index.html) for more.
-#[macro_export]
-macro_rules! guilty {
-    // These are the user facing invocations:
+            guilty! { #[allow(clippy::needless_pub_self)] pub(self) trait Toll { const FEE: u8; } }
+            guilty! { pub(super) trait Levy { const RATE: u8; } }
+            guilty! { #[guilty(introspect)] pub(in tests::kingdom) trait Tithe: Levy { const SHARE: u8 = 10; } }
+            guilty! {
+                /// # Safety
+                /// Only the crown may implement this
+                pub(in self::super) unsafe trait Crown<'a> where Self: 'a { const SEAL: &'a str; }
+            }
+            guilty! { pub(super) extend trait ::std::fmt::Debug as Herald { const CRY: &'static str = "hear ye"; } }
+            pub struct Village;
+            guilty! { impl Toll for Village { const FEE: u8 = 2; } }
+            pub fn fee() -> u8 { guilty!(Village::FEE) }
+        }
+        guilty! { impl province::Levy for province::Village { const RATE: u8 = 3; } }
+        guilty! { impl province::Tithe for province::Village { } }
+        guilty! { unsafe impl<'a> province::Crown<'a> for province::Village { const SEAL: &'a str = "lion"; } }
+        guilty! { impl province::Herald for () { } }
+        pub fn dues() -> (u8, u8, u8, usize, &'static str, &'static str) {
+            use self::province::{Crown, Herald, Levy, Tithe, Village};
+            (province::fee(), guilty!(Village::RATE), guilty!(Village::SHARE), Village::__guilty_const_names().len(),
+             guilty!(Village::SEAL), guilty!(<() as Herald>::CRY))
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(introspect, fingerprint, object_safe)]
+        trait Labeled {
+            #[guilty(per_variant)] const TAG: u8;
+            #[guilty(per_variant)] const TAG_NAME: &'static str = "untagged";
+            const WIDTH: u8 = 1;
+        }
+    }
+    #[derive(Clone, Copy)]
+    enum Signal { Ping, Pong(u8) }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(fingerprint, also_for_ref, literals_mod = signal_literals)]
+        impl Labeled for Signal {
+            #[guilty(per_variant)]
+            const TAG: u8 = match self { Signal::Ping => 1, Signal::Pong(n) => 2 + n };
+            #[guilty(per_variant)]
+            /// The name of the variant
+            const TAG_NAME: &'static str = match self { Signal::Ping => "ping", Signal::Pong(_) => "pong" };
+            const WIDTH: u8 = 2;
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { impl Signal { #[guilty(per_variant)] pub const IS_PING: bool = match self { Signal::Ping => true, _ => false }; } }
+    #[cfg(not(feature = "real-consts"))]
+    fn tags<T: Labeled>(values: &[T]) -> Vec<(u8, &'static str)> { values.iter().map(|v| (guilty!(v.TAG), guilty!(v.TAG_NAME))).collect() }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(fingerprint, object_safe)]
+        trait Block {
+            #[guilty(typenum = LEN_T)]
+            /// The number of bytes
+            const LEN: usize;
+            #[guilty(typenum = ALIGN_T)] const ALIGN: usize = 4;
+        }
+    }
+    struct Sector;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(fingerprint, also_for_ref, literals_mod = sector_literals)]
+        impl Block for Sector { #[guilty(typenum = LEN_T)] const LEN: usize = 32; #[guilty(typenum = ALIGN_T)] const ALIGN: usize = 4; }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    fn type_len<B: Block>() -> (usize, usize) {
+        use typenum::Unsigned;
+        (B::LEN_T::to_usize(), B::ALIGN_T::to_usize())
+    }
+    #[cfg(not(feature = "real-consts"))]
+    fn local_consts() -> (u8, usize, String) {
+        guilty! { #[guilty(introspect)] trait Local { const ONE: u8; const TWO: u8 = 2; } }
+        struct Here;
+        guilty! { #[guilty(literals_mod = here_literals)] impl Local for Here { const ONE: u8 = 1; } }
+        guilty!(impl Here { const THREE: u8 = 3; });
+        guilty_expr! {
+            assert_eq!(Here::ONE + <Here as Local>::TWO, Here::THREE, "{} + {}", Here::ONE, Here::TWO);
+            (guilty!(literal here_literals::ONE), Here::__guilty_const_names().len(), format!("{:?}", vec![Here::ONE; 2]))
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    fn darkness<T: Tinted>() -> (u8, u16) { (guilty!(<T as Tinted>::INK), guilty!(<T as Tinted>::HUE)) }
 
-    // 1. define a private trait
-    ($(#[$attr:meta])* trait $traitname:ident $body:tt) => {
-        guilty!(INTERNAL: DEFINE TRAIT, [$(#[$attr])*] [trait] [$traitname], $body);
-    };
-    // 2. define a private trait with inheritance
-    ($(#[$attr:meta])* trait $traitname:ident : $parent:ident $body:tt) => {
-        guilty!(INTERNAL: DEFINE TRAIT, [$(#[$attr])*] [pub trait] [$traitname : $parent], $body);
-    };
-    // 3a. define a public trait
-    ($(#[$attr:meta])* pub trait $traitname:ident $body:tt) => {
-        guilty!(INTERNAL: DEFINE TRAIT, [$(#[$attr])*] [pub trait] [$traitname], $body);
-    };
-    // 3b. define a public restricted trait
-    ($(#[$attr:meta])* pub $restr:tt trait $traitname:ident $body:tt) => {
-        guilty!(INTERNAL: DEFINE TRAIT, [$(#[$attr])*] [pub $restr trait] [$traitname], $body);
-    };
-    // 4a. define a public trait with inheritance
-    ($(#[$attr:meta])* pub trait $traitname:ident : $parent:ident $body:tt) => {
-        guilty!(INTERNAL: DEFINE TRAIT, [$(#[$attr])*] [pub trait] [$traitname : $parent], $body);
-    };
-    // 4b. define a public restricted trait with inheritance
-    ($(#[$attr:meta])* pub $restr:tt trait $traitname:ident : $parent:ident $body:tt) => {
-        guilty!(INTERNAL: DEFINE TRAIT, [$(#[$attr])*] [pub $restr trait] [$traitname : $parent], $body);
-    };
-    // 5. implement a trait (public or private)
-    (impl $traitname:ident for $structname:ident $body:tt) => {
-        guilty!(INTERNAL: DEFINE IMPL, $traitname, $structname, $body);
-    };
-    // 6a. access a const declared with this macro (mentioning trait)
-    (<$structname:ident as $traitname:ident> :: $constname:ident) => {
-        guilty!(INTERNAL: ACCESS CONST, (<$structname as $traitname>), $constname);
-    };
-    // 6b. access a const declared with this macro (w/o mentioning trait)
-    ($structname:ident :: $constname:ident) => {
-        guilty!(INTERNAL: ACCESS CONST, ($structname), $constname);
-    };
+    guilty! { trait Banner { const TEXT: &'static str; const GLYPHS: &'static [u8]; } }
+    struct Motd;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        impl Banner for Motd {
+            #[guilty(static)]
+            const TEXT: &'static str = concat!("guilt", "-by-", "association");
+            #[guilty(static)]
+            /// The widths of the glyphs
+            const GLYPHS: &'static [u8] = &[8, 8, 16];
+        }
+    }
 
-    // Following are the internal macro calls
-    // Since you can't export a macro which calls other unexported macros, guilty! calls itself
-    // recursively in order to continue parsing. The invocation syntax for all these recursive
-    // calls starts with the tokens `INTERNAL:`.
-    //
-    // The general strategy for parsing these declarations is we parse one const declaration from
-    // the beginning of the trait/impl at a time, turning it into a static function which is
-    // appended to the end of the trait/impl. When there are no more consts, the recursion stops
-    // and the trait/impl is outputted (with an indirection through AS ITEM to appease the parser).
+    guilty! { trait Weight { const GRAMS: u32; fn doubled(&self) -> u32 { guilty!(<Self as Weight>::GRAMS) * 2 } } }
+    trait Storage { type Unit; }
+    struct Brick;
+    struct Pallet;
+    guilty! { impl Weight for Brick { const GRAMS: u32 = 50; } }
+    impl Storage for Pallet { type Unit = Brick; }
+    fn unit_grams<S: Storage>() -> u32 where S::Unit: Weight {
+        guilty!(<S::Unit as Weight>::GRAMS) + guilty!(<<S as Storage>::Unit as Weight>::GRAMS)
+    }
 
+    #[cfg(not(feature = "real-consts"))]
+    fn heavy_units<S: Storage>(count: u32) -> u32 where S::Unit: Weight {
+        guilty_expr! {
+            let total = count * <S::Unit as Weight>::GRAMS;
+            let pallet = guilty_expr!(Brick::GRAMS) * guilty!(self::Brick::GRAMS);
+            if total > Brick::GRAMS { total + pallet.min(<u32>::MAX) } else { [Brick::GRAMS, u32::min_value()][1] }
+        }
+    }
 
-    // parse-trait-defconst: parse a trait with a const (that has a default value) as the first declaration
-    // the square brackets contain [trait Trait] or [pub trait Trait]
-    // this calls on to:
-    //  - itself if there is another default-valued const
-    //  - parse-trait-nodefconst if there is another const with no default value
-    //  - def-trait-fn/def-trait-attr/def-trait-ty if there are no more consts
-    (INTERNAL: DEFINE TRAIT, [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*],
-     {
-         $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constdefault:expr;
-         $($body:tt)*
-     }) => {
-        guilty!(INTERNAL: DEFINE TRAIT, [$(#[$attr])*] [$($before)+] [$($traitname)*],
-                {
-                    $($body)*
-                    $(#[$cattr])* #[allow(non_snake_case)] fn $constname() -> $consttype { $constdefault }
-                });
-    };
-    // parse-trait-nodefconst: parse a trait with a const (that has no default value) as the first declaration
-    // this calls on to:
-    //  - itself is there is another non-default-valued const
-    //  - parse-trait-defconst if there is another default-valued const
-    //  - def-trait-fn/def-trait-attr/def-trait-ty if there are no more consts
-    (INTERNAL: DEFINE TRAIT, [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*],
-     {
-         $(#[$cattr:meta])* const $constname:ident : $consttype:ty;
-         $($body:tt)*
-     }) => {
-        guilty!(INTERNAL: DEFINE TRAIT, [$(#[$attr])*] [$($before)+] [$($traitname)*],
-                {
-                    $($body)*
-                    $(#[$cattr])* #[allow(non_snake_case)] fn $constname() -> $consttype;
-                });
-    };
-    // def-trait-fn: output a trait that has no consts at the beginning (starts with an unadorned fn)
-    // indirection through item-redir
-    (INTERNAL: DEFINE TRAIT, [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*],
-     {
-         $(#[$fattr:meta])* fn $($body:tt)*
-     }) => {
-        guilty!(INTERNAL: AS ITEM, $(#[$attr])* $($before)+ $($traitname)* { $(#[$fattr])* fn $($body)* });
-    };
-    // def-trait-attr: output a trait that has no consts at the beginning (starts with fn that has
-    //    docs/attributes)
-    // indirection through item-redir
-    (INTERNAL: DEFINE TRAIT, [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*],
-     {
-         # $($body:tt)*
-     }) => {
-        guilty!(INTERNAL: AS ITEM, $(#[$attr])* $($before)+ $($traitname)* { # $($body)* });
-    };
-    // def-trait-ty: output a trait that has no consts at the beginning (starts with an associated type)
-    // indirection through item-redir
-    (INTERNAL: DEFINE TRAIT, [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*],
-     {
-         $(#[$tattr:meta])* type $($body:tt)*
-     }) => {
-        guilty!(INTERNAL: AS ITEM, $(#[$attr])* $($before)+ $($traitname)* { $(#[$tattr])* type $($body)* });
-    };
-    // def-trait-empty: output a trait that has no items
-    (INTERNAL: DEFINE TRAIT, [$(#[$attr:meta])*] [$($before:tt)+] [$($traitname:tt)*],
-     {
-     }) => {
-        guilty!(INTERNAL: AS ITEM, $(#[$attr])* $($before)+ $($traitname)* { });
-    };
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(self_consts)]
+        trait Round {
+            const PI: f64 = 3.0;
+            fn area(&self) -> f64 { Self::PI * Self::RADIUS * Self::RADIUS }
+            const RADIUS: f64;
+            fn rounded() -> i64 { (Self::PI * 10.0).round() as i64 + Self::offset() }
+            fn offset() -> i64 { 0 }
+            const DIAMETER: f64 = Self::RADIUS * 2.0;
+        }
+    }
+    struct Coin;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(self_consts)]
+        impl Round for Coin {
+            const RADIUS: f64 = 2.0;
+            const PI: f64 = { let rough = 3.1; rough - (Self::RADIUS - 2.0) };
+            fn offset() -> i64 { if Self::RADIUS > 1.0 { [Self::RADIUS as i64][0] } else { 0 } }
+        }
+    }
 
-    // parse-impl-const: parse an impl with a const as the first declaration
-    // calls on to:
-    //  - itself if there is another const
-    //  - def-impl-fn/def-impl-ty if there are no more consts
-    (INTERNAL: DEFINE IMPL, $traitname:path, $structname:ident,
-     {
-         $(#[$cattr:meta])* const $constname:ident : $consttype:ty = $constvalue:expr;
-         $($body:tt)*
-     }) => {
-        guilty!(INTERNAL: DEFINE IMPL, $traitname, $structname,
-                {
-                    $($body)*
-                    $(#[$cattr])* #[allow(non_snake_case)] fn $constname() -> $consttype { $constvalue }
-                });
-    };
-    // def-impl-fn: output an impl that has no consts at the beginning (starts with fn)
-    // indirection through item-redir
-    (INTERNAL: DEFINE IMPL, $traitname:path, $structname:ident,
-     {
-         $(#[$fattr:meta])* fn $($body:tt)*
-     }) => {
-        guilty!(INTERNAL: AS ITEM, impl $traitname for $structname { $(#[$fattr])* fn $($body)* });
-    };
-    // def-impl-ty: output an impl that has no consts at the beginning (starts with type)
-    // indirection through item-redir
-    (INTERNAL: DEFINE IMPL, $traitname:path, $structname:ident,
-     {
-         $(#[$tattr:meta])* type $($body:tt)*
-     }) => {
-        guilty!(INTERNAL: AS ITEM, impl $traitname for $structname { $(#[$tattr])* type $($body)* });
-    };
-    // def-impl-empty: output an impl that has no items in it
-    (INTERNAL: DEFINE IMPL, $traitname:path, $structname:ident,
-     {
-     }) => {
-        guilty!(INTERNAL: AS ITEM, impl $traitname for $structname { });
-    };
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(object_safe, erased = DynFilter)]
+        trait Filter {
+            const NAME: &'static str;
+            fn run(&self, input: u32) -> u32;
+            const PRIORITY: u8 = 5;
+            #[guilty(no_reflect)]
+            const SECRET: u32 = 1 + 1;
+        }
+    }
+    struct Doubler;
+    struct Squarer;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { impl Filter for Doubler { const NAME: &'static str = "double"; fn run(&self, input: u32) -> u32 { input * 2 } } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { impl Filter for Squarer { fn run(&self, input: u32) -> u32 { input * input } const NAME: &'static str = "square"; const PRIORITY: u8 = 1; } }
+    #[cfg(not(feature = "real-consts"))]
+    fn priority_of<F: Filter>(filter: &F) -> u8 {
+        guilty!(filter.PRIORITY)
+    }
 
-    // access: access a const defined with this macro
-    // For now, it just calls the function, since we turn consts into functions. In the future, it
-    // might do something more clever if the implementation changes.
-    (INTERNAL: ACCESS CONST, ($($structname:tt)*), $constname:ident) => {{
-        $($structname)* :: $constname ()
-    }};
+    struct Limits;
+    guilty! {
+        impl Limits {
+            pub const MAX: usize = 16;
+            fn new() -> Self { Limits }
+            const MIN: usize = guilty!(Self::MAX) / 4;
+            pub(crate) fn span(&self) -> usize { guilty!(Self::MAX) - guilty!(Self::MIN) }
+        }
+    }
+    guilty! { impl<T> Wrapper<T> where T: Clone { pub const WRAPPED: bool = true; } }
 
-    // item-redir: Item redirection.
-    // For some reason the parser sometimes complains "expected item" when you are trying to output
-    // a perfectly good item. The solution (sometimes) is to redirect through a macro like this.
-    (INTERNAL: AS ITEM, $i:item) => ($i)
-}
+    #[cfg(not(feature = "real-consts"))]
+    guilty_mod! {
+        mod units {
+            use std::{fmt, str};
+            /// A unit of length
+            pub trait Unit { const SYMBOL: &'static str; const SCALE: u32 = 1; fn describe(&self) -> String { ::guilty!(Self::SYMBOL).into() } }
+            pub struct Meter;
+            pub struct Kilometer(pub u8);
+            impl Unit for Meter { const SYMBOL: &'static str = "m"; }
+            #[guilty(inherent = pub)]
+            impl Unit for Kilometer { const SYMBOL: &'static str = "km"; const SCALE: u32 = 1000; }
+            pub const SCALES: [u32; 2] = { let km = 1000; [1, km] };
+            pub fn scale_of<U: Unit>() -> u32 { ::guilty!(U::SCALE) }
+            pub type Parsed = Result<u32, <u32 as str::FromStr>::Err>;
+            pub fn parse_scale(s: &str) -> Parsed { s.parse() }
+            pub fn describe_scale(scale: u32) -> String { fmt::format(format_args!("x{}", scale)) }
+            pub mod nested {
+                pub(crate) trait Prefixed { const PREFIX: char; }
+                impl Prefixed for super::Kilometer { const PREFIX: char = 'k'; }
+            }
+        }
+    }
+    #[cfg(not(feature = "real-consts"))]
+    guilty_mod! {
+        unsafe impl RawBytes for Mile { }
+        struct Mile;
+        impl units::Unit for Mile { const SYMBOL: &'static str = "mi"; const SCALE: u32 = 1609; }
+    }
 
-#[cfg(test)]
-mod tests {
-    // some small tests
-    guilty! { trait Empty { } }
-    guilty! { trait JustFn { fn foo(&self); } }
-    guilty! { trait JustType { type Foo; } }
-    guilty! { pub trait JustConst { const FOO: (); } }
-    guilty! { trait DocFn { #[doc="bar"] fn foo(&self); } }
-    guilty! { pub(crate) trait DocType { #[doc="bar"] type Foo; } }
-    guilty! { trait DocConst { #[doc="bar"] const FOO: (); } }
-    struct Foo;
-    guilty! { impl Empty for Foo { } }
-    guilty! { impl JustFn for Foo { fn foo(&self) {} } }
-    guilty! { impl JustType for Foo { type Foo = (); } }
-    guilty! { impl JustConst for Foo { const FOO: () = (); } }
-    guilty! { impl DocFn for Foo { #[doc="bar"] fn foo(&self) {} } }
-    guilty! { impl DocType for Foo { #[doc="bar"] type Foo = (); } }
-    guilty! { impl DocConst for Foo { #[doc="bar"] const FOO: () = (); } }
+    guilty! {
+        /// # Safety
+        /// Implementors promise that ALIGN is a power of two
+        unsafe trait Alloc { const ALIGN: usize; fn align(&self) -> usize { guilty!(Self::ALIGN) } }
+    }
+    guilty! {
+        /// # Safety
+        /// The all-zero bit pattern must be valid when ZEROABLE is true
+        pub unsafe trait RawBytes { const ZEROABLE: bool = true; }
+    }
+    guilty! {
+        /// # Safety
+        /// PINNED must only be true for types that never move
+        pub(crate) unsafe trait Pinned<'a> { const PINNED: bool; }
+    }
+    struct Arena;
+    guilty! { unsafe impl Alloc for Arena { const ALIGN: usize = 8; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[guilty(inherent)] unsafe impl RawBytes for Arena { const ZEROABLE: bool = false; } }
+    guilty! { unsafe impl<'a> Pinned<'a> for Wrapper<&'a u8> { const PINNED: bool = true; } }
+
+    guilty! { trait Vector { const LANES: usize; } }
+    struct Scalar;
+    struct Simd;
+    guilty! { #[cfg(all())] #[allow(clippy::identity_op)] impl Vector for Scalar { const LANES: usize = 1 * 1; } }
+    guilty! { #[cfg(any())] impl Vector for Scalar { const LANES: usize = 2; } }
+    #[cfg(not(feature = "real-consts"))]
+    guilty! { #[doc = "Four lanes"] #[guilty(inherent)] #[cfg(not(any()))] impl Vector for Simd { const LANES: usize = 4; } }
+    guilty! { #[cfg(any())] #[guilty(inherent)] impl Vector for Simd { const LANES: usize = 8; } }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(erased = DynWord, name_of, aggregates(max, sum), track_overrides, fingerprint)]
+        trait Word {
+            /// Words in a cache line
+            #[cfg(target_pointer_width = "64")] const WORDS: usize;
+            #[cfg(not(target_pointer_width = "64"))] const WORDS: usize;
+            #[cfg(target_pointer_width = "64")] const BITS: usize = 64;
+            #[cfg(not(target_pointer_width = "64"))] const BITS: usize = 32;
+        }
+    }
+    struct Machine;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(inherent, track_overrides, fingerprint, literals_mod = machine_literals, also_for_ref)]
+        impl Word for Machine {
+            #[cfg(target_pointer_width = "64")] const WORDS: usize = 8;
+            #[cfg(not(target_pointer_width = "64"))] const WORDS: usize = 16;
+        }
+    }
+
+    // commas after the consts, and stray separators
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        trait Tally {
+            const ONES: u8,
+            const TENS: u8 = 10,
+            const PARSED: Result<u8, u8> = Ok::<u8, u8>(1),;
+            fn total(&self) -> u8;
+        }
+    }
+    struct Abacus;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(self_consts)]
+        impl Tally for Abacus {
+            const ONES: u8 = 3;
+            const TENS: u8 = if 1 < 2 { Self::ONES * 7 - 1 } else { 30 },
+            fn total(&self) -> u8 { Self::ONES + Self::TENS }
+            ,
+        }
+    }
+
+    // a big trait and impl (within the default recursion limit)
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(fingerprint)]
+        trait ControlCodes {
+            const NUL: u8,
+            const SOH: u8,
+            const STX: u8,
+            const ETX: u8,
+            const EOT: u8,
+            const ENQ: u8,
+            const ACK: u8,
+            const BEL: u8,
+            const BS: u8,
+            const HT: u8,
+            const LF: u8,
+            const VT: u8,
+            const FF: u8,
+            const CR: u8,
+            const SO: u8,
+            const SI: u8,
+            const DLE: u8,
+            const DC1: u8,
+            const DC2: u8,
+            const DC3: u8,
+            const DC4: u8,
+            const NAK: u8,
+            const SYN: u8,
+            const ETB: u8,
+            const CAN: u8,
+            const EM: u8,
+            const SUB: u8,
+            const ESC: u8,
+            const FS: u8,
+            const GS: u8,
+            const RS: u8,
+            const US: u8,
+            const DEL: Option<u8> = Some(0x7F);
+            fn is_control(&self, byte: u8) -> bool;
+        }
+    }
+    struct Ascii;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(fingerprint)]
+        impl ControlCodes for Ascii {
+            const NUL: u8 = 0x00,
+            const SOH: u8 = 0x01,
+            const STX: u8 = 0x02,
+            const ETX: u8 = 0x03,
+            const EOT: u8 = 0x04,
+            const ENQ: u8 = 0x05,
+            const ACK: u8 = 0x06,
+            const BEL: u8 = 0x07,
+            const BS: u8 = 0x08,
+            const HT: u8 = 0x09,
+            const LF: u8 = 0x0A,
+            const VT: u8 = 0x0B,
+            const FF: u8 = 0x0C,
+            const CR: u8 = 0x0D,
+            const SO: u8 = 0x0E,
+            const SI: u8 = 0x0F,
+            const DLE: u8 = 0x10,
+            const DC1: u8 = 0x11,
+            const DC2: u8 = 0x12,
+            const DC3: u8 = 0x13,
+            const DC4: u8 = 0x14,
+            const NAK: u8 = 0x15,
+            const SYN: u8 = 0x16,
+            const ETB: u8 = 0x17,
+            const CAN: u8 = 0x18,
+            const EM: u8 = 0x19,
+            const SUB: u8 = 0x1A,
+            const ESC: u8 = 0x1B,
+            const FS: u8 = 0x1C,
+            const GS: u8 = 0x1D,
+            const RS: u8 = 0x1E,
+            const US: u8 = 0x1F,
+            fn is_control(&self, byte: u8) -> bool { byte <= guilty!(Self::US) || Some(byte) == guilty!(Self::DEL) }
+        }
+    }
+
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        #[guilty(introspect)]
+        trait ServerConfig {
+            const PORT: u16;
+            const HOST: &'static str = "localhost";
+            #[cfg(not(test))] const UNUSED: u8 = 0;
+            #[guilty(no_reflect)] const TOKEN: &'static str;
+        }
+    }
+    struct DevServer;
+    #[cfg(not(feature = "real-consts"))]
+    guilty! {
+        impl ServerConfig for DevServer {
+            const PORT: u16 = 8080;
+            const TOKEN: &'static str = "hunter2";
+        }
+    }
+
+    #[cfg(not(feature = "real-consts"))]
+    macro_rules! limit_of {
+        ($t:ty, $name:ident) => { guilty!(const $t, $name) };
+        ($t:ty as $tr:path, $name:ident) => { guilty!(const $t as $tr, $name) };
+    }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn small() {
         assert_eq!(guilty!(<Foo as JustConst>::FOO), ());
         assert_eq!(guilty!(<Foo as DocConst>::FOO), ());
+        let just_fn: &dyn JustFn = &Foo;
+        just_fn.foo();
+        assert_eq!(guilty2!(<Square<u8> as Shape<f32>>::SIDES), 4);
+        assert_eq!(guilty2!(<Square<u8> as Shape<f32>>::NAME), "shape");
+        assert_eq!(Task::run(Box::new(Foo)), 1);
+        assert_eq!(Task::share(::std::rc::Rc::new(Foo)), 3);
+        assert_eq!(Task::run(Box::new(Bar(0, 9))), 9);
+        assert_eq!(Task::share(::std::rc::Rc::new(Bar(0, 9))), 5);
+        assert_eq!(Job::run(::std::sync::Arc::new(Foo)), 6);
+        assert_eq!(Job::pinned(::std::pin::Pin::new(&mut Foo)), 7);
+        assert_eq!(Square(4u8).scaled(), 8.0);
+        assert_eq!(Square(4u8).describe("square"), ("square", 4));
+        assert_eq!(guilty!(<Foo as Borrowed>::NAME), "foo");
+        assert_eq!(guilty!(<Foo as Nicknamed>::NICKNAME), "nick");
+        assert_eq!(Foo.name(), "foo");
+        assert_eq!((Wrapper("a"), 0).arity(), 2);
+        assert_eq!(guilty!(<(u8, u8) as Footprint>::BYTES), 2);
+        assert_eq!(guilty!(<[f32; 3] as Footprint>::BYTES), 12);
+        assert_eq!(guilty!(<&'static str as Footprint>::BYTES), 16);
+        assert_eq!(guilty!(<Vec<u8> as Footprint>::BYTES), 24);
+        assert_eq!(guilty!(<shapes::Dot as Footprint>::BYTES), 0);
+        assert_eq!(Countdown(3).render(), "#3");
+        assert_eq!(guilty!(<app::Settings as my_traits::Config>::RETRIES), 3);
+        assert!(!guilty!(<app::Settings as my_traits::Config>::VERBOSE));
+        assert!(guilty!(<Foo as my_traits::Config>::VERBOSE));
+        assert_eq!((guilty!(crate::tests::app::Settings::TIMEOUT), guilty!(::tests::app::Settings::TIMEOUT)), (30, 30));
+        assert_eq!(guilty!(<::tests::app::Remote as ::tests::my_traits::Config>::RETRIES), 5);
+        assert!(!guilty!(<crate::tests::app::Remote as crate::tests::my_traits::Config>::VERBOSE));
+        assert_eq!(app::client::timeout(), 60);
+        assert_eq!(Brick.doubled(), 100);
+        assert_eq!(unit_grams::<Pallet>(), 100);
+        assert_eq!(guilty!(<Brick>::GRAMS), 50);
+        assert_eq!(guilty!(super::tests::Brick::GRAMS), 50);
+        assert_eq!((guilty!(Limits::MAX), guilty!(Limits::MIN), Limits::new().span()), (16, 4, 12));
+        assert!(guilty!(Wrapper::<u8>::WRAPPED));
+        #[cfg(any(not(feature = "proc-macro"), feature = "const-fn"))]
+        assert_eq!(PALETTE[..], [8; 16]);
+        assert!(!guilty!(<::std::ops::Range<u32> as IteratorConsts>::ENDLESS));
+        assert!(guilty!(<::std::iter::Repeat<u8> as IteratorConsts>::ENDLESS));
+        assert_eq!(guilty!(<Host as Build>::PROFILE), if cfg!(feature = "provenance") { "debug" } else { "release" });
+    }
+
+    // the guilty options and the other macros depend on the consts being functions
+    #[test]
+    #[cfg(not(feature = "real-consts"))]
+    fn fake_consts() {
+        assert_eq!(guilty!(Foo::SIZE), 0);
+        assert_eq!(guilty!(<Bar as Layout>::SIZE), ::std::mem::size_of::<Bar>());
+        assert_eq!(guilty!(<Bar as Layout>::ALIGN), ::std::mem::align_of::<Bar>());
+        assert_eq!(guilty!(Host::CONSTS_FINGERPRINT), guilty!(Plugin::CONSTS_FINGERPRINT));
+        assert!(guilty!(Host::CONSTS_FINGERPRINT) != guilty!(Drifted::CONSTS_FINGERPRINT));
+        assert_eq!(limit_of!(Host, LIMIT), 8);
+        assert_eq!(limit_of!(Drifted as Config, LIMIT), 9);
+        assert_eq!(guilty!(<Foo as Thresholds>::CONSTS_MAX), 100);
+        assert_eq!(guilty!(<Foo as Thresholds>::CONSTS_MIN), 10);
+        assert_eq!(guilty!(<Foo as Thresholds>::CONSTS_SUM), 160);
+        assert_eq!(<Foo as Thresholds>::name_of(&50), Some("REJECT"));
+        assert_eq!(<Foo as Thresholds>::name_of(&51), None);
+
+        assert!(guilty!(overridden <Host as Backend>::ID));
+        assert!(!guilty!(overridden <Host as Backend>::NAME));
+        assert!(guilty!(overridden <Plugin as Backend>::NAME));
+        guilty!(assert_overridden Backend::ID for Host);
+        assert!(::std::panic::catch_unwind(|| guilty!(assert_overridden Backend::ID for Host, Plugin)).is_err());
+
+        <Foo as Range>::assert_invariants();
+        assert!(::std::panic::catch_unwind(<Bar as Range>::assert_invariants).is_err());
+        assert_eq!(guilty!(Foo::MAX), 10);
+        // in a debug build, the invariants are checked whenever a const of the impls with
+        // check_invariants is accessed
+        let access = ::std::panic::catch_unwind(|| guilty!(Bar::MIN));
+        assert_eq!(access.is_err(), cfg!(debug_assertions));
+        let dyn_capacity: &dyn Capacity = &Foo;
+        assert_eq!(dyn_capacity.slots(), guilty!(Foo::SLOTS));
+        assert_eq!(guilty!(unmangled <Foo as Handwritten>::LIMIT), 3);
+        assert_eq!(<Bar as Handwritten>::LIMIT(), 4);
+
+        assert_eq!(guilty!(Foo::TIMEOUT_MS), 500);
+        {
+            let _outer = guilty!(override <Foo as Retry>::TIMEOUT_MS = 5);
+            assert_eq!(guilty!(Foo::TIMEOUT_MS), 5);
+            {
+                let _inner = guilty!(override Foo::TIMEOUT_MS = 7);
+                assert_eq!(guilty!(Foo::TIMEOUT_MS), 7);
+                assert_eq!(::std::thread::spawn(|| guilty!(Foo::TIMEOUT_MS)).join().unwrap(), 500);
+            }
+            assert_eq!(guilty!(Foo::TIMEOUT_MS), 5);
+        }
+        assert_eq!(guilty!(Foo::TIMEOUT_MS), 500);
+
+        assert_eq!(capacity::<Foo>(), 16);
+        assert!(!guilty!(<Foo as buffer_consts::Buffer>::GROWABLE));
+        assert_eq!(Buffer::CAPACITY(&Foo), 0);
+
+        assert_eq!(guilty!(View::NAME), "view");
+        assert_eq!(View("borrowed").name(), "borrowed");
+
+        assert_eq!(guilty!(const Wrapper<u8>, N), 1);
+        assert_eq!(guilty!(const &Wrapper<u8>, N), 1);
+
+        assert_eq!(Wrapper('w').copies().len(), 2);
+
+        assert_eq!(Foo.plus(1u8), 21);
+        assert_eq!(Bar(0, 0).plus(1u8), 11);
+        assert_eq!((guilty!(<Foo as mixed_consts::Mixed>::FIRST), Foo.kind()), (1, "foo"));
+        assert_eq!(guilty!(<Bar as mixed_consts::Mixed>::FIRST), 2);
+        assert!(guilty!(overridden <Foo as Mixed>::SECOND));
+        assert!(!guilty!(overridden <Bar as Mixed>::SECOND));
+
+        assert_eq!(guilty!(<Countdown as tagged_consts::Tagged>::TAG), 7);
+
+        assert_eq!(guilty!(Wrapper::<u8>::N), 1);
+        assert_eq!(guilty_expr!(Brick::GRAMS + <Brick as Weight>::GRAMS * (Brick::GRAMS - 49)), 100);
+        assert_eq!(heavy_units::<Pallet>(2), 2600);
+        assert_eq!(heavy_units::<Pallet>(1), 0);
+        assert_eq!(local_consts(), (1, 2, "[1, 1]".to_string()));
+        assert_eq!(Coin.area(), 3.1 * 4.0);
+        assert_eq!(Coin::rounded(), 33);
+        assert_eq!(guilty!(Coin::DIAMETER), 4.0);
+        let plugins: Vec<Box<dyn Filter>> = vec![Box::new(Doubler), Box::new(Squarer)];
+        assert_eq!(plugins.iter().fold(3, |x, p| p.run(x)), 36);
+        let erased: Vec<&dyn DynFilter> = vec![&Doubler, &Squarer];
+        assert_eq!(erased.iter().map(|p| guilty!(p.PRIORITY)).collect::<Vec<_>>(), [5, 1]);
+        let squarer = Squarer;
+        assert_eq!((guilty!(squarer.NAME), guilty!((erased[0]).PRIORITY), priority_of(&Doubler)), ("square", 5, 5));
+        assert_eq!((guilty!(<Squarer as Filter>::NAME), guilty!(<Doubler as Filter>::SECRET)), ("square", 2));
+        assert_eq!((units::Unit::describe(&units::Meter), units::scale_of::<units::Kilometer>()), ("m".to_string(), 1000));
+        assert_eq!((guilty!(units::Kilometer::SYMBOL), units::SCALES[1], guilty!(<Mile as units::Unit>::SCALE)), ("km", 1000, 1609));
+        assert_eq!((units::parse_scale("1000"), units::describe_scale(3)), (Ok(1000), "x3".to_string()));
+        assert_eq!(guilty!(<units::Kilometer as units::nested::Prefixed>::PREFIX), 'k');
+        assert_eq!((Arena.align(), guilty!(Arena::ZEROABLE), guilty!(<Wrapper<&u8> as Pinned>::PINNED)), (8, false, true));
+        assert_eq!((guilty!(Scalar::LANES), guilty!(Simd::LANES)), (1, 4));
+        let bits = guilty!(<Machine as Word>::BITS);
+        assert_eq!((guilty!(Machine::WORDS) * bits, guilty!(literal machine_literals::WORDS) * bits), (512, 512));
+        assert_eq!((guilty!((&&Machine as &dyn DynWord).WORDS), guilty!(<&Machine as Word>::WORDS)), (512 / bits, 512 / bits));
+        assert_eq!((Machine::name_of(&bits), guilty!(Machine::CONSTS_MAX), guilty!(Machine::CONSTS_SUM)), (Some("BITS"), bits, bits + 512 / bits));
+        assert_eq!(guilty!(Machine::CONSTS_FINGERPRINT),
+                   ::__fingerprint_extend(<Machine as Word>::__guilty_trait_fingerprint(),
+                                          if bits == 64 { "WORDS:usize=8;" } else { "WORDS:usize=16;" }));
+        assert_eq!((Abacus.total(), guilty!(Abacus::PARSED)), (23, Ok(1)));
+        assert_eq!((guilty!(Ascii::ESC), guilty!(Ascii::DEL)), (0x1B, Some(0x7F)));
+        assert!(Ascii.is_control(b'\n') && !Ascii.is_control(b'a'));
+        let mut dump = Vec::new();
+        DevServer::__guilty_describe(&mut |name, ty, value| dump.push(format!("{}: {} = {:?}", name, ty, value)));
+        assert_eq!(DevServer::__guilty_const_names(), ["PORT", "HOST"]);
+        assert_eq!(dump, ["PORT: u16 = 8080", "HOST: &'static str = \"localhost\""]);
+        assert_eq!(FRAME_BUFFER.len(), 3);
+        assert_eq!(frame_version_name(3), "frame");
+        assert_eq!(guilty!(literal frame_literals::ID), guilty!(Frame::ID));
+        assert_eq!((FRAME_LABEL, match guilty!(Frame::ID) { frame_literals!(ID) => 1, _ => 0 }), ("frame/v3", 1));
+        assert_eq!((guilty!(Motd::TEXT), guilty!(Motd::GLYPHS)), ("guilt-by-association", &[8, 8, 16][..]));
+        assert!(::std::ptr::eq(guilty!(Motd::GLYPHS), guilty!(Motd::GLYPHS)));
+        assert_eq!((guilty!(Mono::LANES), Stereo.lanes(), guilty!(<Duplex<u8, Duplex<u16, u32>>>::LANES)), (4, 4, 4));
+        assert_eq!((guilty!(<Vec<u8> as Lanes>::LANES), Some(1u8).lanes()), (1, 1));
+        assert_eq!((guilty!(<u8 as DisplayConsts>::WIDTH), guilty!(<str as DisplayConsts>::RIGHT_ALIGNED)), (8, false));
+        assert_eq!(padded("guilt"), "   guilt");
+        assert_eq!((Primary.open(), Primary.open(), Primary.open(), Replica.open()), (true, true, false, true));
+        assert_eq!(guilty!(Primary::LIVE).load(::std::sync::atomic::Ordering::SeqCst), 3);
+        assert_eq!(guilty!(<Replica as Pooled>::LIVE).load(::std::sync::atomic::Ordering::SeqCst), 2);
+        assert!(::std::ptr::eq(guilty!(Replica::NAMES), guilty!(Replica::NAMES)) && guilty!(Replica::NAMES)[1] == "us");
+        let _: &dyn Pooled = &Primary;
+        assert_eq!((guilty!(Grid::KEY)[31], guilty!(Grid::SCALE)(2), guilty!(Grid::NEXT), guilty!(Grid::LABELS)), (7, 6, Some(Grid), &["x", "y"][..]));
+        assert_eq!((guilty!(Grid::CELLS), guilty!(Grid::MERGE)(200, 100), guilty!(Grid::ROW)[7]), (vec![(1, [2, 3])], None, 1));
+        assert_eq!((guilty!(Shelf::EMPTY), guilty!(Shelf::DEPTH), Shelf.top()), (' ', 4, 'z'));
+        let front: <Shelf as queue_consts::Queue>::Entry = guilty!(<Shelf as queue_consts::Queue>::FRONT);
+        assert_eq!((front, Shelf.back()), (1, 9));
+        assert_eq!((Canvas::default(), Slot::<char>::default()), (Canvas(3), Slot(None)));
+        guilty_assert_eq!(<Host as Config>::LIMIT, <Plugin as Config>::LIMIT);
+        guilty_assert_eq!(<Canvas as Blank>::INK, 3, "ink of {:?}", Canvas(3));
+        guilty_assert_eq!(<Slot<u8>>::INK, <Slot<char> as Blank>::INK);
+        assert_eq!((heavier::<Gold, Iron>(), Iron.symbol(), Gold.symbol(), guilty!(Ore::<Iron>::DENSITY), ALLOYS.len()),
+                   (true, "Fe", "Au", 0, 2));
+        assert_eq!(Iron.to_string(), "iron");
+        assert_eq!(kingdom::dues(), (2, 3, 10, 1, "lion", "hear ye"));
+        assert_eq!(tags(&[Signal::Ping, Signal::Pong(3)]), [(1, "ping"), (5, "pong")]);
+        assert_eq!(tags(&[&Signal::Pong(0)]), [(2, "pong")]);
+        assert_eq!(guilty!((&Signal::Pong(1) as &dyn Labeled).TAG), 3);
+        assert_eq!((guilty!((Signal::Ping).IS_PING), guilty!((Signal::Pong(0)).IS_PING), signal_literals::WIDTH), (true, false, 2));
+        assert_eq!(<Signal as Labeled>::__guilty_const_names(), ["WIDTH"]);
+        assert_eq!((type_len::<Sector>(), type_len::<&Sector>(), guilty!(Sector::LEN), sector_literals::LEN), ((32, 4), (32, 4), 32, 32));
+        assert_eq!((darkness::<Canvas>(), <Canvas as Tinted>::__guilty_const_names()), ((3, 120), &["HUE"][..]));
+        assert!(::std::panic::catch_unwind(|| guilty_assert_eq!(<Host as Config>::LIMIT, <Drifted as Config>::LIMIT)).is_err());
+        guilty_assert_type!(Grid::KEY: [u8; 32]);
+        guilty_assert_type!(<Grid as Layouts>::ROW: &'static [u16; 8]);
+        assert_eq!((guilty!((&Studio as &dyn DynTuning).RATE), guilty!(literal studio_literals::KEY), guilty!(Studio::KEY)), (48_000, 42, 42));
+        assert_eq!(guilty!(Studio::CONSTS_FINGERPRINT), ::__fingerprint_extend(::__fingerprint("RATE:u32=44_100;"), "RATE:u32=48_000;"));
+        assert_eq!(guilty!(<Tenant as Retention>::CONSTS_FINGERPRINT),
+                   ::__fingerprint_extend(::__fingerprint("SOFT:u32=10;HARD:u32;"), "HARD:u32=100;"));
+        assert!(guilty!(<Tenant as Retention>::CONSTS_FINGERPRINT) != guilty!(<Tenant as retention_v2::Retention>::CONSTS_FINGERPRINT));
+        let threads = (0..4).map(|_| ::std::thread::spawn(|| guilty!(Lookup::SQUARES)[15])).collect::<Vec<_>>();
+        assert!(threads.into_iter().all(|thread| thread.join().unwrap() == 225));
+        assert_eq!((guilty!(Lookup::SQUARES)[3], SQUARES_BUILT.load(::std::sync::atomic::Ordering::SeqCst)), (9, 1));
+        assert_eq!(guilty!(Column::<u8>::WIDTHS), &[1, 2, 4]);
+        assert!(::std::ptr::eq(guilty!(Column::<u8>::WIDTHS), guilty!(Column::<String>::WIDTHS)));
+        assert_eq!(guilty!(Frame::MAGIC), 0xCAFE0);
+        assert_eq!(guilty!(<[u8; 0] as Fixed>::LEN), 0);
+        assert_eq!(guilty!(<[u8; 17] as Fixed>::LEN), 17);
+        assert_eq!(guilty!(<[i16; 32] as Fixed>::LEN), 32);
+        assert!(guilty!(<[i16; 32] as Fixed>::ZEROED));
+        assert_eq!([7u8, 8].first(), Some(7));
+
+        let (trait_file, trait_line) = guilty!(location <Msg as Protocol>::VERSION).unwrap();
+        let (impl_file, impl_line) = guilty!(location Msg::PORT).unwrap();
+        assert_eq!((trait_file, impl_file), (file!(), file!()));
+        assert!(trait_line < impl_line);
+        assert_eq!(guilty!(location Msg::BOGUS), None);
+        let mut log = Log(vec![1, 2, 3]);
+        assert_eq!(tag_of(&log), 1);
+        assert_eq!(batch_of(&&mut log), 8);
+        assert_eq!(<&mut Log as Sink>::describe(&&mut log, true), "log of 3");
+        assert_eq!(<&mut Log as Sink>::kind(), "log");
+        assert_eq!(Sink::flush(&mut &mut log), 3);
+        let mut boxed = Box::new(Log(vec![4]));
+        assert_eq!(batch_of(&boxed), 8);
+        assert_eq!(boxed.flush(), 1);
+        assert_eq!(<Box<Log> as Sink>::describe(&boxed, false), "log");
+        assert_eq!((boxed.mode(), <&mut Log as Sink>::mode(&&mut log)), ("test", "test"));
+        assert!(::std::ptr::eq(guilty!(<Box<Log> as Sink>::OPENED), guilty!(Log::OPENED)));
+        assert_eq!(guilty!(pool::Pool::SMALL), 4);
+        guilty_assert_unique!(Backend::ID for Host, Plugin);
+        guilty_assert_unique!(CONNECTIONS for Host);
+        assert!(::std::panic::catch_unwind(|| guilty_assert_unique!(Config::LIMIT for Host, Drifted, Plugin)).is_err());
+        assert_eq!(backend_name(0, "!"), Some("plugin!".to_string()));
+        assert_eq!(backend_name(1, "?"), Some("backend?".to_string()));
+        assert_eq!(backend_name(2, "!"), None);
+        assert_eq!(guilty!(<Drifted as Quota>::LIMIT), 9);
+        assert_eq!(guilty!(<Drifted as Quota>::LABEL), "x");
+        assert_eq!(guilty!(<Drifted as Quota>::BURST), 1);
+        assert_eq!(origin::<D2>().len(), 2);
+        assert_eq!(origin::<D3>().len(), 3);
+
+        ::std::env::set_var("GUILT_BY_ASSOCIATION_TEST_TIMEOUT", "250");
+        ::std::env::set_var("GUILT_BY_ASSOCIATION_TEST_RETRIES", "lots");
+        assert_eq!(guilty!(<Bar as Retry>::TIMEOUT_MS), 250);
+        assert_eq!(guilty!(<Bar as Retry>::RETRIES), 3);
+        ::std::env::set_var("GUILT_BY_ASSOCIATION_TEST_TIMEOUT", "100");
+        assert_eq!(guilty!(<Bar as Retry>::TIMEOUT_MS), 250);
+
+        assert_eq!(wire_header::<Msg>(), (0x2a, 3, 0));
+        assert_eq!(guilty!(<Msg as Wire>::TAG), 0x2a);
+        assert_eq!(guilty!(Host::CONNECTIONS), 16);
+        assert_eq!(guilty!(Host::POOL_SIZE), 16);
+
+        assert_eq!(guilty_try_const!(<Foo as Range>::MAX: u32 else 0), 10);
+        assert_eq!(guilty_try_const!(<Host as Range>::MAX: u32 else 0), 0);
+        assert_eq!(guilty_try_const!(<[u8; 5] as Fixed>::LEN: usize else 0), 5);
+        assert_eq!(guilty_try_const!(<Vec<u8> as Fixed>::LEN: usize else 0), 0);
+        // in generic code, the bound is not known, so even an impl gets the fallback
+        assert_eq!(try_max::<Foo>(), 0);
+
+        assert_eq!((guilty!(<Vault as Secrets>::SALT), guilty!(<Keyring as Secrets>::SALT)), (99, 42));
+        assert_eq!(<Vault as Secrets>::name_of(&1), Some("PUBLIC"));
+        assert_eq!(<Vault as Secrets>::name_of(&99), None);
+        assert!(guilty!(overridden <Vault as Secrets>::PUBLIC));
+        assert!(!guilty!(overridden <Vault as Secrets>::SALT));
+        assert_eq!(guilty!(location <Vault as Secrets>::SALT), None);
+        assert_eq!(guilty!(<Vault as Secrets>::CONSTS_FINGERPRINT), guilty!(<Keyring as Secrets>::CONSTS_FINGERPRINT));
+        let secrets: &dyn ErasedSecrets = &Vault;
+        assert_eq!(guilty!(secrets.PUBLIC), 1);
+
+        let erased: Vec<Box<dyn ErasedMeta>> = vec![Box::new(Host), Box::new(Plugin)];
+        assert_eq!(erased.iter().map(|m| (guilty!(m.NAME), guilty!(m.VERSION))).collect::<Vec<_>>(),
+                   [("host", 1), ("plugin", 2)]);
     }
 
 
@@ -220,6 +6031,7 @@ mod tests {
 
     guilty! {
         /// A trait for things that do stuff
+        #[allow(non_upper_case_globals)]
         trait Trait {
             /// An associated const with a default
             const WithDefault: i32 = 0;
@@ -275,6 +6087,168 @@ mod tests {
 }
 
 
+#[cfg(all(test, feature = "proc-macro"))]
+#[allow(dead_code)]
+mod proc_macro_tests {
+    use {guilty, guilty_item};
+
+    guilty! {
+        trait Generic<T> {
+            fn get(&self) -> T;
+            const SIZE: usize;
+            const DEFAULT: Option<T> = None;
+        }
+    }
+
+    struct Wrapper<T>(T);
+
+    guilty! {
+        impl<T: Clone + From<u8>> Generic<T> for Wrapper<T> where T: Default {
+            fn get(&self) -> T { self.0.clone() }
+            const SIZE: usize = ::std::mem::size_of::<T>();
+        }
+    }
+
+    #[guilty_item]
+    trait Attr {
+        fn twice(&self) -> u8 { guilty!(Self::ONCE) * 2 }
+        const ONCE: u8 = 1;
+    }
+
+    #[cfg(not(feature = "real-consts"))]
+    #[guilty_item(inherent)]
+    impl Attr for Wrapper<u8> {
+        const ONCE: u8 = 2;
+    }
+
+    // the mangled names don't clash with the methods of the type
+    guilty! {
+        trait Length {
+            const LEN: usize;
+            const HALF: usize = guilty!(Self::LEN) / 2;
+        }
+    }
+
+    #[guilty_item]
+    impl Length for Wrapper<char> {
+        const LEN: usize = 6;
+    }
+
+    #[cfg(not(feature = "real-consts"))]
+    impl Wrapper<char> {
+        #[allow(non_snake_case)]
+        fn LEN(&self) -> usize { 1 }
+    }
+
+    guilty! {
+        #[guilty(unmangled)]
+        trait Count {
+            const COUNT: usize = 2;
+        }
+    }
+
+    #[guilty_item(unmangled)]
+    impl Count for Wrapper<char> {}
+
+    #[test]
+    fn generic() {
+        assert_eq!(guilty!(<Wrapper<u32> as Generic<u32>>::SIZE), 4);
+        assert_eq!(guilty!(<Wrapper<u32> as Generic<u32>>::DEFAULT), None);
+        assert_eq!(Wrapper(5u32).get(), 5);
+        assert_eq!(guilty!(Wrapper::<char>::LEN), 6);
+        assert_eq!(guilty!(<Wrapper<char> as Length>::HALF), 3);
+        assert_eq!(guilty!(unmangled Wrapper::<char>::COUNT), 2);
+    }
+
+    #[test]
+    #[cfg(not(feature = "real-consts"))]
+    fn functions() {
+        assert_eq!(Wrapper(0u8).twice(), 4);
+        assert_eq!(guilty!(Wrapper::<u8>::ONCE), 2);
+        assert_eq!((guilty!(Wrapper::<char>::LEN), Wrapper('x').LEN()), (6, 1));
+        assert_eq!(<Wrapper<char> as Count>::COUNT(), 2);
+    }
+}
+
+#[cfg(all(test, feature = "real-consts"))]
+#[allow(dead_code)]
+mod real_consts_tests {
+    #[cfg(feature = "proc-macro")]
+    use guilty;
+
+    guilty! {
+        pub trait Shape {
+            const SIDES: u32;
+            fn name(&self) -> &'static str;
+            const CORNERS: u32 = Self::SIDES;
+        }
+    }
+
+    guilty! {
+        trait Weight { const GRAMS: u16; }
+        struct Feather;
+        impl Weight for Feather { const GRAMS: u16 = 1; }
+    }
+
+    struct Square;
+    struct Circle;
+
+    guilty! {
+        impl Shape for Square {
+            fn name(&self) -> &'static str { "square" }
+            const SIDES: u32 = 4;
+        }
+    }
+
+    guilty! {
+        impl Shape for Circle {
+            const SIDES: u32 = 1;
+            const CORNERS: u32 = 0;
+            fn name(&self) -> &'static str { "circle" }
+        }
+    }
+
+    struct Triangle;
+    struct Wedge;
+
+    guilty! {
+        impl Shape for Triangle, Wedge {
+            const SIDES: u32 = 3;
+            fn name(&self) -> &'static str { "triangle" }
+        }
+    }
+
+    guilty! {
+        #[guilty(blanket)]
+        extend trait ::std::fmt::Debug as DebugConsts {
+            const LABEL: &'static str = "debug";
+        }
+    }
+
+    // usable where a real const is required
+    const SQUARE_SIDES: u32 = guilty!(Square::SIDES);
+    static CIRCLE_CORNERS: [u8; guilty!(<Circle as Shape>::CORNERS) as usize] = [];
+
+    fn sides<S: Shape>() -> u32 {
+        guilty!(const S as Shape, SIDES)
+    }
+
+    #[test]
+    fn real() {
+        assert_eq!(SQUARE_SIDES, 4);
+        assert_eq!(guilty!(Square::CORNERS), 4);
+        assert_eq!(guilty!(const Circle, CORNERS), 0);
+        assert_eq!(sides::<Circle>(), 1);
+        assert_eq!(CIRCLE_CORNERS.len(), 0);
+        assert_eq!(Square.name(), "square");
+        assert_eq!((guilty!(Wedge::CORNERS), Triangle.name()), (3, "triangle"));
+        assert_eq!(guilty!(<u8 as DebugConsts>::LABEL), "debug");
+        guilty_assert_eq!(<Wedge>::CORNERS, 3);
+        guilty_assert_type!(Circle::CORNERS: u32);
+        assert_eq!(guilty!(Feather::GRAMS), 1);
+    }
+}
+
 /*
  * BEFORE
  *